@@ -0,0 +1,57 @@
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::{criterion_group, criterion_main};
+use mosfhet::prelude::*;
+
+#[rustfmt::skip]
+static UPPER_N_VALUES: [(f64, u32, u32, u32); 2] = [
+    (5.513429641723633e-08, 1024, 2, 10), // 1.85 * (2 ** -25)
+    (4.884981308350689e-16, 2048, 1, 23), // 1.10 * (2 ** -51)
+];
+
+fn functional_bootstrap(crit: &mut Criterion) {
+    // Create a new benchmark group
+    let mut group = crit.benchmark_group("functional_bootstrap");
+
+    // Use the same input key for every parameter set
+    let lower_n = 630;
+    let sigma = 3.0517578125e-05;
+    let torus_base = 4;
+    let tlwe_key = TlweKey::new_binary(lower_n, sigma);
+    let tlwe = Tlwe::new(Torus::from_unsigned(1, 3), &tlwe_key);
+
+    // Iterate through the TRLWE parameters
+    for (sigma, upper_n, l, bg_bit) in UPPER_N_VALUES {
+        // Generate new keys
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let bk_ga_key = BootstrapGaKey::new(&tlwe_key, &trgsw_key);
+
+        // Generate an identity test vector
+        let tv = TestVector::new(|x| x)
+            .with_input_precision(2)
+            .with_output_precision(3)
+            .trlwe(1, upper_n);
+
+        // Benchmark the standard blind rotation
+        let bench_id = BenchmarkId::new("standard", upper_n);
+        group.bench_function(bench_id, |b| {
+            b.iter(|| tlwe.functional_bootstrap(&tv, &bk_key, torus_base));
+        });
+
+        // Benchmark the blind rotation with automorphisms
+        let bench_id = BenchmarkId::new("ga", upper_n);
+        group.bench_function(bench_id, |b| {
+            b.iter(|| {
+                tlwe.functional_bootstrap_ga(&tv, &bk_ga_key, torus_base)
+            });
+        });
+    }
+
+    // Finish the benchmark group
+    group.finish();
+}
+
+criterion_group!(benches, functional_bootstrap);
+criterion_main!(benches);
@@ -33,9 +33,9 @@ impl fmt::Display for ParamSet {
 
 #[rustfmt::skip]
 static SIGMA_UPPER_N_VALUES: [(f64, u32); 3] = [
-    (5.6152343750000000e-04, 512), // 1.15 * (2 ** -11)
-    (5.5134296417236328e-08, 1024), // 1.85 * (2 ** -25)
-    (4.8849813083506888e-16, 2048), // 1.10 * (2 ** -51)
+    (5.615234375e-04, 512), // 1.15 * (2 ** -11)
+    (5.513429641723633e-08, 1024), // 1.85 * (2 ** -25)
+    (4.884981308350689e-16, 2048), // 1.10 * (2 ** -51)
 ];
 
 fn trlwe_trgsw_mul(crit: &mut Criterion) {
@@ -1,19 +1,17 @@
+use mosfhet::noise;
 use mosfhet::prelude::*;
 
 fn main() {
     // TFHE parameters
-    let upper_n = 2048;
-    let sigma = 4.884981308350689e-16f64;
-    let bg_bit = 23;
-    let l = 1;
-    let k = 1;
+    let params = ParamSet::SMALL;
+    let upper_n = params.upper_n();
 
     // Define number of LUTs and output precision
     let num_luts = 2048usize;
 
     // Generate new keys
-    let trlwe_key = TrlweKey::new(upper_n, k, sigma);
-    let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+    let trlwe_key = params.trlwe_key();
+    let trgsw_key = params.trgsw_key(&trlwe_key);
 
     // Calculate log2(num_luts)
     let log_luts = num_luts.next_power_of_two().ilog2() as usize;
@@ -62,9 +60,14 @@ fn main() {
         // Calculate the variance
         let variance = sum_variances / num_values as f64;
 
+        // Predict the variance from the noise model
+        let fresh = noise::fresh(&params);
+        let predicted = noise::cmux_tree(&params, i + 1, fresh, fresh);
+
         // Display the variance
         println!(
             "Error Variance (step {i} - {num_values} values): {variance:e}"
         );
+        println!("Error Variance (step {i} - predicted): {predicted:e}");
     }
 }
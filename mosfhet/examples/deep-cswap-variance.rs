@@ -2,18 +2,15 @@ use mosfhet::prelude::*;
 
 fn main() {
     // TFHE parameters
-    let upper_n = 2048;
-    let sigma = 4.884981308350689e-16f64;
-    let bg_bit = 23;
-    let l = 1;
-    let k = 1;
+    let params = ParamSet::SMALL;
+    let upper_n = params.upper_n();
 
     // Define number of LUTs and output precision
     let num_luts = 2048usize;
 
     // Generate new keys
-    let trlwe_key = TrlweKey::new(upper_n, k, sigma);
-    let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+    let trlwe_key = params.trlwe_key();
+    let trgsw_key = params.trgsw_key(&trlwe_key);
 
     // Calculate log2(num_luts)
     let log_luts = num_luts.next_power_of_two().ilog2() as usize;
@@ -12,18 +12,15 @@ pub fn deep_cswap(in_: &mut [Trlwe], sel: &[TrgswDft]) {
 
 fn main() {
     // TFHE parameters
-    let upper_n = 2048u32;
-    let sigma = 4.884981308350689e-16f64;
-    let bg_bit = 23;
-    let l = 1;
-    let k = 1;
+    let params = ParamSet::SMALL;
+    let upper_n = params.upper_n();
 
     // Define number of LUTs
     let num_luts: usize = 16;
 
     // Generate new keys
-    let trlwe_key = TrlweKey::new(upper_n, k, sigma);
-    let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+    let trlwe_key = params.trlwe_key();
+    let trgsw_key = params.trgsw_key(&trlwe_key);
     let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
 
     // Calculate log2(upper_n) and ceil(log2(num_luts))
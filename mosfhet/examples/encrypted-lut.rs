@@ -0,0 +1,50 @@
+use mosfhet::prelude::*;
+use rand::Rng;
+
+fn main() {
+    // TFHE parameters
+    let params = ParamSet::SMALL;
+
+    // Define the table size (spanning several polynomials) and precision
+    let size = 3000usize;
+    let precision = 8;
+
+    // Generate new keys
+    let trlwe_key = params.trlwe_key();
+    let trgsw_key = params.trgsw_key(&trlwe_key);
+    let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+
+    // Encrypt a table of squares
+    let f = |x: usize| (x * x) as RawTorus % (1 << precision);
+    let mut enc_lut = EncryptedLut::from_fn(size, &trlwe_key, |x| {
+        Torus::from_unsigned(f(x), precision)
+    });
+
+    // Encrypt the bits of an address
+    let encrypt = |x: usize, bits: usize| {
+        TrgswDftArray::from_fn(bits, &trgsw_key, |i| {
+            (Torus::from_raw((x as RawTorus >> i) & 1), 0)
+        })
+    };
+
+    // Read the table at a random encrypted address
+    let plt_addr = rand::thread_rng().gen_range(0..size);
+    let enc_addr = encrypt(plt_addr, enc_lut.address_bits());
+    let value = enc_lut.read(&enc_addr).phase(&tlwe_key);
+    println!("f({plt_addr}) = {}", value.into_unsigned(precision));
+
+    // Read within the block of 16 entries starting at a plaintext offset
+    let offset = 16 * rand::thread_rng().gen_range(0..size / 16);
+    let plt_addr = rand::thread_rng().gen_range(0..16);
+    let enc_addr = encrypt(plt_addr, 4);
+    let value = enc_lut.read_offset(&enc_addr, offset).phase(&tlwe_key);
+    let x = offset + plt_addr;
+    println!("f({x}) = {}", value.into_unsigned(precision));
+
+    // Obliviously add 3 to a random entry and read it back
+    let plt_addr = rand::thread_rng().gen_range(0..size);
+    let enc_addr = encrypt(plt_addr, enc_lut.address_bits());
+    enc_lut.increment(&enc_addr, Torus::from_unsigned(3, precision));
+    let value = enc_lut.read(&enc_addr).phase(&tlwe_key);
+    println!("f({plt_addr}) + 3 = {}", value.into_unsigned(precision));
+}
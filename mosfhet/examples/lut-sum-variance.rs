@@ -2,9 +2,9 @@ use mosfhet::prelude::*;
 
 fn main() {
     // TFHE parameters
-    let upper_n = 2048;
-    let sigma = 4.884981308350689e-16f64;
-    let k = 1;
+    let params = ParamSet::SMALL;
+    let upper_n = params.upper_n();
+    let k = params.k();
 
     // Define number of LUTs and sums
     let num_luts = 16;
@@ -14,7 +14,7 @@ fn main() {
     dbg!(num_sums);
 
     // Generate new keys
-    let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+    let trlwe_key = params.trlwe_key();
 
     // Instantiate zeroed noiseless LUTs
     let mut enc_lut = TrlweArray::zeroed_noiseless(num_luts, k, upper_n);
@@ -5,9 +5,9 @@ use rand::Rng;
 
 fn main() {
     // TFHE parameters
-    let upper_n = 2048u32;
-    let sigma = 4.884981308350689e-16f64;
-    let k = 1;
+    let params = ParamSet::SMALL;
+    let upper_n = params.upper_n();
+    let k = params.k();
 
     // Define number of LUTs and output precision
     let num_luts = 1usize;
@@ -19,7 +19,7 @@ fn main() {
     dbg!(out_prec);
 
     // Generate new keys
-    let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+    let trlwe_key = params.trlwe_key();
     let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
 
     // Instantiate zeroed noiseless LUTs
@@ -253,6 +253,8 @@ void trgsw_DFT_add(TRGSW_DFT out, TRGSW_DFT in1, TRGSW_DFT in2);
 void trlwe_subto(TRLWE out, TRLWE in);
 void trlwe_DFT_mul_by_polynomial(TRLWE_DFT out, TRLWE_DFT in, DFT_Polynomial in2);
 void trlwe_DFT_mul_addto_by_polynomial(TRLWE_DFT out, TRLWE_DFT in, DFT_Polynomial in2);
+void trlwe_mul_by_int(TRLWE out, TRLWE in, Integer in2);
+void trlwe_mul_by_int_polynomial(TRLWE out, TRLWE in, IntPolynomial in2);
 void trlwe_mul_by_xai(TRLWE out, TRLWE in, int a);
 void trlwe_mul_by_xai_addto(TRLWE out, TRLWE in, int a);
 void trlwe_mul_by_xai_minus_1(TRLWE out, TRLWE in, int a);
@@ -424,6 +426,8 @@ void blind_rotate_ga(TRLWE tv, Torus * a, TRGSW_DFT * s, TRLWE_KS_Key * ak, int
 void functional_bootstrap_wo_extract_ga(TRLWE out, TRLWE tv, TLWE in, Bootstrap_GA_Key key, int torus_base);
 void functional_bootstrap_ga(TLWE out, TRLWE tv, TLWE in, Bootstrap_GA_Key key, int torus_base);
 void free_bootstrap_key_ga(Bootstrap_GA_Key key);
+void save_bootstrap_key_ga(FILE * fd, Bootstrap_GA_Key key);
+Bootstrap_GA_Key load_new_bootstrap_key_ga(FILE * fd);
 
 #ifdef __cplusplus
 }
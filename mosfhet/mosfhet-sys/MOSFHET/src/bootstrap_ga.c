@@ -32,6 +32,47 @@ void free_bootstrap_key_ga(Bootstrap_GA_Key key){
 }
 
 
+void save_bootstrap_key_ga(FILE * fd, Bootstrap_GA_Key key){
+  fwrite(&key->n, sizeof(int), 1, fd);
+  fwrite(&key->l, sizeof(int), 1, fd);
+  fwrite(&key->k, sizeof(int), 1, fd);
+  fwrite(&key->N, sizeof(int), 1, fd);
+  fwrite(&key->Bg_bit, sizeof(int), 1, fd);
+  fwrite(&key->unfolding, sizeof(int), 1, fd);
+  for (size_t i = 0; i < key->n; i++){
+    trgsw_save_DFT_sample(fd, key->s[i]);
+  }
+  for (size_t i = 0; i < key->N; i++){
+    trlwe_save_KS_key(fd, key->ak[i]);
+  }
+}
+
+Bootstrap_GA_Key load_new_bootstrap_key_ga(FILE * fd){
+  Bootstrap_GA_Key res;
+  res = (Bootstrap_GA_Key) safe_malloc(sizeof(*res));
+  fread(&res->n, sizeof(int), 1, fd);
+  fread(&res->l, sizeof(int), 1, fd);
+  fread(&res->k, sizeof(int), 1, fd);
+  fread(&res->N, sizeof(int), 1, fd);
+  fread(&res->Bg_bit, sizeof(int), 1, fd);
+  fread(&res->unfolding, sizeof(int), 1, fd);
+  res->s = (TRGSW_DFT *) safe_malloc(sizeof(TRGSW_DFT) * res->n);
+  for (size_t i = 0; i < res->n; i++){
+    res->s[i] = trgsw_load_new_DFT_sample(fd, res->l, res->Bg_bit, res->k, res->N);
+  }
+  res->ak = (TRLWE_KS_Key *) safe_malloc(sizeof(TRLWE_KS_Key) * res->N);
+  for (size_t i = 0; i < res->N; i++){
+    res->ak[i] = trlwe_load_new_KS_key(fd);
+  }
+  return res;
+}
+
+
+// Rounds to the nearest odd integer, so that forcing the low bit does not bias the rotation
+static inline uint64_t torus2odd(Torus x, int log_scale){
+  const uint64_t bit_size = sizeof(Torus) * 8;
+  return ((x >> (bit_size - log_scale + 1)) << 1) | 1;
+}
 
 // Blind rotate 
 // Algorithm 4 in https://eprint.iacr.org/2022/198.pdf
@@ -41,17 +82,17 @@ void blind_rotate_ga(TRLWE tv, Torus * a, TRGSW_DFT * s, TRLWE_KS_Key * ak, int
   TRLWE rotated_tv = trlwe_alloc_new_sample(tv->k, N);
   TRLWE_DFT tmp = trlwe_alloc_new_DFT_sample(tv->k, N);
 
-  const uint64_t w0p = inverse_mod_2N(torus2int(a[0], log_N2)|1, N);
+  const uint64_t w0p = inverse_mod_2N(torus2odd(a[0], log_N2), N);
   trlwe_eval_automorphism(rotated_tv, tv, w0p, ak[(w0p - 1)>>1]);
   for (size_t i = 0; i < size - 1; i++){
-    const uint64_t a_i = torus2int(a[i], log_N2)|1;
-    const uint64_t w_ip1 = inverse_mod_2N(torus2int(a[i+1], log_N2)|1, N);
+    const uint64_t a_i = torus2odd(a[i], log_N2);
+    const uint64_t w_ip1 = inverse_mod_2N(torus2odd(a[i+1], log_N2), N);
     const uint64_t gen = (a_i*w_ip1)&mod_mask;
     trgsw_mul_trlwe_DFT(tmp, rotated_tv, s[i]);
     trlwe_from_DFT(tv, tmp);
     trlwe_eval_automorphism(rotated_tv, tv, gen, ak[(gen - 1)>>1]);
   }
-  const uint64_t a_n = torus2int(a[size -1], log_N2)|1;
+  const uint64_t a_n = torus2odd(a[size -1], log_N2);
   trgsw_mul_trlwe_DFT(tmp, rotated_tv, s[size - 1]);
   trlwe_from_DFT(rotated_tv, tmp);
   trlwe_eval_automorphism(tv, rotated_tv, a_n, ak[(a_n - 1)>>1]);
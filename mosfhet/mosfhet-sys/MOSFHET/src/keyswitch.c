@@ -409,7 +409,7 @@ Generic_KS_Key trlwe_load_new_generic_ks_key(FILE * fd){
   res->n = n;
   res->include_b = include_b;
 
-  res->s = (TRLWE ***) safe_malloc(sizeof(TRLWE**) * n);
+  res->s = (TRLWE ***) safe_malloc(sizeof(TRLWE**) * (n + include_b));
   for (size_t i = 0; i < n + include_b; i++){
     res->s[i] = (TRLWE **) safe_malloc(sizeof(TRLWE*) * t);
       for (size_t j = 0; j < t; j++){
@@ -158,7 +158,6 @@ void polynomial_subto_torus_polynomial(TorusPolynomial out, TorusPolynomial in){
 void torus_polynomial_mul_by_xai(TorusPolynomial out, TorusPolynomial in, int a){
   const int N = out->N;
   a &= ((N<<1) - 1); // a % 2N
-  if (!a) return;
   if (a < N) {
     for (int i = 0; i < a; i++) out->coeffs[i] = -in->coeffs[i - a + N];
     for (int i = a; i < N; i++) out->coeffs[i] = in->coeffs[i - a];
@@ -172,7 +171,6 @@ void torus_polynomial_mul_by_xai(TorusPolynomial out, TorusPolynomial in, int a)
 void torus_polynomial_mul_by_xai_addto(TorusPolynomial out, TorusPolynomial in, int a){
   const int N = out->N;
   a &= ((N<<1) - 1); // a % 2N
-  if (!a) return;
   if (a < N) {
     for (int i = 0; i < a; i++) out->coeffs[i] += -in->coeffs[i - a + N];
     for (int i = a; i < N; i++) out->coeffs[i] += in->coeffs[i - a];
@@ -229,8 +227,8 @@ void polynomial_mul_torus(TorusPolynomial out, TorusPolynomial in1, TorusPolynom
   const int N = in2->N;
   // alloc temporaries
   static __thread DFT_Polynomial * tmp_pool[32] = {NULL}; 
-  if(tmp_pool[N>>10] == NULL) tmp_pool[N>>10] = polynomial_new_array_of_polynomials_DFT(N, 3);
-  DFT_Polynomial * tmp = tmp_pool[N>>10];
+  if(tmp_pool[__builtin_ctz(N)] == NULL) tmp_pool[__builtin_ctz(N)] = polynomial_new_array_of_polynomials_DFT(N, 3);
+  DFT_Polynomial * tmp = tmp_pool[__builtin_ctz(N)];
   // dft mul
   polynomial_torus_to_DFT(tmp[1], in1);
   polynomial_torus_to_DFT(tmp[2], in2);
@@ -242,8 +240,8 @@ void polynomial_mul_addto_torus(TorusPolynomial out, TorusPolynomial in1, TorusP
   const int N = in2->N;
   // alloc temporaries
   static __thread TorusPolynomial tmp_pool[32] = {NULL}; 
-  if(tmp_pool[N>>10] == NULL) tmp_pool[N>>10] = polynomial_new_torus_polynomial(N);
-  TorusPolynomial tmp = tmp_pool[N>>10];
+  if(tmp_pool[__builtin_ctz(N)] == NULL) tmp_pool[__builtin_ctz(N)] = polynomial_new_torus_polynomial(N);
+  TorusPolynomial tmp = tmp_pool[__builtin_ctz(N)];
   polynomial_mul_torus(tmp, in1, in2);
   polynomial_addto_torus_polynomial(out, tmp);
 }
@@ -293,32 +291,32 @@ void polynomial_torus_scale(TorusPolynomial out, TorusPolynomial in, int log_sca
 __thread FFT_Processor_Spqlios fft_proc[32] = {NULL};
 
 void init_fft(int N){
-  if(!fft_proc[N >> 10]) fft_proc[N >> 10] = new_FFT_Processor_Spqlios(N);
+  if(!fft_proc[__builtin_ctz(N)]) fft_proc[__builtin_ctz(N)] = new_FFT_Processor_Spqlios(N);
 }
 #else
 #include "./fft/ffnt/ffnt.h"
-__thread FFT_Processor_FFNT fft_proc[8] = {NULL};
+__thread FFT_Processor_FFNT fft_proc[32] = {NULL};
 
 void init_fft(int N){
-  if(!fft_proc[N >> 10]) fft_proc[N >> 10] = new_FFT_Processor_FFNT(N);
+  if(!fft_proc[__builtin_ctz(N)]) fft_proc[__builtin_ctz(N)] = new_FFT_Processor_FFNT(N);
 }
 #endif
 
 void polynomial_DFT_to_torus(TorusPolynomial out, const DFT_Polynomial in){
   init_fft(out->N);
 #ifdef TORUS32
-  execute_direct_torus32(out->coeffs, in->coeffs, fft_proc[in->N >> 10]);
+  execute_direct_torus32(out->coeffs, in->coeffs, fft_proc[__builtin_ctz(in->N)]);
 #else
-  execute_direct_torus64(out->coeffs, in->coeffs, fft_proc[in->N >> 10]);
+  execute_direct_torus64(out->coeffs, in->coeffs, fft_proc[__builtin_ctz(in->N)]);
 #endif
 }
 
 void polynomial_torus_to_DFT(DFT_Polynomial out, TorusPolynomial in){
   init_fft(in->N);
 #ifdef TORUS32
-  execute_reverse_torus32(out->coeffs, in->coeffs, fft_proc[in->N >> 10]);
+  execute_reverse_torus32(out->coeffs, in->coeffs, fft_proc[__builtin_ctz(in->N)]);
 #else
-  execute_reverse_torus64(out->coeffs, in->coeffs, fft_proc[in->N >> 10]);
+  execute_reverse_torus64(out->coeffs, in->coeffs, fft_proc[__builtin_ctz(in->N)]);
 #endif
 }
 
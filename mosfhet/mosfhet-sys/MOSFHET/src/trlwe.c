@@ -247,6 +247,26 @@ void trlwe_negate(TRLWE out, TRLWE in){
   polynomial_negate_torus_polynomial(out->b, in->b);
 }
 
+void trlwe_mul_by_int(TRLWE out, TRLWE in, Integer in2){
+  const int N = in->b->N;
+  for (size_t i = 0; i < in->k; i++){
+    for (size_t j = 0; j < N; j++){
+      out->a[i]->coeffs[j] = in->a[i]->coeffs[j]*in2;
+    }
+  }
+  for (size_t j = 0; j < N; j++){
+    out->b->coeffs[j] = in->b->coeffs[j]*in2;
+  }
+}
+
+/* out = in * in2, with in2 an integer polynomial */
+void trlwe_mul_by_int_polynomial(TRLWE out, TRLWE in, IntPolynomial in2){
+  for (size_t i = 0; i < in->k; i++){
+    polynomial_naive_mul_torus(out->a[i], in->a[i], in2);
+  }
+  polynomial_naive_mul_torus(out->b, in->b, in2);
+}
+
 void trlwe_DFT_copy(TRLWE_DFT out, TRLWE_DFT in){
   for (size_t i = 0; i < in->k; i++){
     polynomial_copy_DFT_polynomial(out->a[i], in->a[i]);
@@ -405,14 +425,8 @@ void trlwe_mv_extract_tlwe(TLWE * out, TRLWE in, int amount){
 }
 
 void trlwe_mv_extract_tlwe_scaling(TLWE out, TRLWE in, int scale){
-  const int N = in->b->N, amount = scale;
-  trlwe_extract_tlwe(out, in, amount/2);
-  for (size_t i = amount/2 + 1; i < amount; i++){
-    trlwe_extract_tlwe_subto(out, in, N - 1 - (i - amount/2));
-  }
-  for (size_t i = 0; i < amount/2; i++){
-    trlwe_extract_tlwe_addto(out, in, i);
-  }
+  tlwe_noiseless_trivial_sample(out, 0);
+  trlwe_mv_extract_tlwe_scaling_addto(out, in, scale);
 }
 
 void trlwe_mv_extract_tlwe_scaling_addto(TLWE out, TRLWE in, int scale){
@@ -520,7 +534,9 @@ void trlwe_tensor_prod(TRLWE out, TRLWE in1, TRLWE in2, int precision, TRLWE_KS_
 }
 
 void trlwe_tensor_prod_FFT(TRLWE out, TRLWE in1, TRLWE in2, int precision, TRLWE_KS_Key rl_key){
-  const int N = in1->b->N, half_prec = sizeof(Torus)*8 - (sizeof(Torus)*8 - precision)/2;
+  const int N = in1->b->N, bit_len = sizeof(Torus)*8;
+  const int half_prec1 = bit_len - (bit_len - precision)/2;
+  const int half_prec2 = bit_len - (bit_len - precision + 1)/2;
   assert(in1->k == 1 && in2->k == 1);
   TorusPolynomial tmp = polynomial_new_torus_polynomial(N);
   DFT_Polynomial tmp_DFT = polynomial_new_DFT_polynomial(N);
@@ -531,16 +547,16 @@ void trlwe_tensor_prod_FFT(TRLWE out, TRLWE in1, TRLWE in2, int precision, TRLWE
   DFT_Polynomial B1 = polynomial_new_DFT_polynomial(N);
   DFT_Polynomial B2 = polynomial_new_DFT_polynomial(N);
   // T = A1 * A2
-  polynomial_torus_scale(tmp, in1->a[0], half_prec);
+  polynomial_torus_scale(tmp, in1->a[0], half_prec1);
   polynomial_torus_to_DFT(A1, tmp);
-  polynomial_torus_scale(tmp, in2->a[0], half_prec);
+  polynomial_torus_scale(tmp, in2->a[0], half_prec2);
   polynomial_torus_to_DFT(A2, tmp);
   polynomial_mul_DFT(t->a[0], A1, A2);
   for (size_t i = 0; i < N; i++) t->b->coeffs[i] = 0.;
   // A = A1*B2 + B1*A2
-  polynomial_torus_scale(tmp, in1->b, half_prec);
+  polynomial_torus_scale(tmp, in1->b, half_prec1);
   polynomial_torus_to_DFT(B1, tmp);
-  polynomial_torus_scale(tmp, in2->b, half_prec);
+  polynomial_torus_scale(tmp, in2->b, half_prec2);
   polynomial_torus_to_DFT(B2, tmp);
   polynomial_mul_DFT(tmp_DFT, A1, B2);
   polynomial_mul_addto_DFT(tmp_DFT, B1, A2);
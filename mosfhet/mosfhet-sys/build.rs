@@ -113,7 +113,6 @@ fn main() {
         .flag("-Wno-unused-result")
         .flag("-Wno-unused-variable")
         .flag("-Wno-unused-parameter")
-        .static_flag(true)
         .compile("libmosfhet.a");
 
     println!("cargo:rustc-link-lib=static=mosfhet");
@@ -136,8 +135,10 @@ fn main() {
         .allowlist_type("TRGSW.*")
         .allowlist_type("TRLWE.*")
         .allowlist_function("blind_rotate")
+        .allowlist_function("circuit_bootstrap.*")
         .allowlist_function(".*2torus")
         .allowlist_function("free_.*")
+        .allowlist_function("full_domain_functional_bootstrap.*")
         .allowlist_function("functional_bootstrap_.*")
         .allowlist_function("generate_.*")
         .allowlist_function("init_fft")
@@ -145,6 +146,7 @@ fn main() {
         .allowlist_function("multivalue_bootstrap_.*")
         .allowlist_function("new_bootstrap_key.*")
         .allowlist_function("polynomial_.*")
+        .allowlist_function("programmable_bootstrap")
         .allowlist_function("save_bootstrap_key.*")
         .allowlist_function("torus.*")
         .allowlist_function("tlwe_.*")
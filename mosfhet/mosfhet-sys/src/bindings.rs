@@ -1633,6 +1633,16 @@ extern "C" {
         in2: DFT_Polynomial,
     );
 }
+extern "C" {
+    pub fn trlwe_mul_by_int(out: TRLWE, in_: TRLWE, in2: Integer);
+}
+extern "C" {
+    pub fn trlwe_mul_by_int_polynomial(
+        out: TRLWE,
+        in_: TRLWE,
+        in2: IntPolynomial,
+    );
+}
 extern "C" {
     pub fn trlwe_mul_by_xai(out: TRLWE, in_: TRLWE, a: ::libc::c_int);
 }
@@ -2234,6 +2244,33 @@ extern "C" {
 extern "C" {
     pub fn load_new_bootstrap_key(fd: *mut FILE) -> Bootstrap_Key;
 }
+extern "C" {
+    pub fn circuit_bootstrap(
+        out: TRGSW,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        kska: Generic_KS_Key,
+        kskb: Generic_KS_Key,
+    );
+}
+extern "C" {
+    pub fn circuit_bootstrap_2(
+        out: TRGSW,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        kska: Generic_KS_Key,
+        kskb: Generic_KS_Key,
+    );
+}
+extern "C" {
+    pub fn circuit_bootstrap_3(
+        out: TRGSW,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        kska: *mut TRLWE_KS_Key,
+        kskb: Generic_KS_Key,
+    );
+}
 extern "C" {
     pub fn multivalue_bootstrap_CLOT21(
         out: *mut TLWE,
@@ -2244,6 +2281,69 @@ extern "C" {
         n_luts: ::libc::c_int,
     );
 }
+extern "C" {
+    pub fn programmable_bootstrap(
+        out: TLWE,
+        tv: TRLWE,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        precision: ::libc::c_int,
+        kappa: ::libc::c_int,
+        theta: ::libc::c_int,
+    );
+}
+extern "C" {
+    pub fn full_domain_functional_bootstrap(
+        out: TLWE,
+        tv: TRLWE,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        tlwe_ksk: TLWE_KS_Key,
+        precision: ::libc::c_int,
+    );
+}
+extern "C" {
+    pub fn full_domain_functional_bootstrap_CLOT21(
+        out: TLWE,
+        tv: *mut TRLWE,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        ksk: Generic_KS_Key,
+        rlk: TRLWE_KS_Key,
+        precision: ::libc::c_int,
+    );
+}
+extern "C" {
+    pub fn full_domain_functional_bootstrap_CLOT21_2(
+        out: TLWE,
+        tv: *mut Torus,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        ksk: Generic_KS_Key,
+        rlk: TRLWE_KS_Key,
+        precision: ::libc::c_int,
+    );
+}
+extern "C" {
+    pub fn full_domain_functional_bootstrap_KS21(
+        out: TLWE,
+        tv: TorusPolynomial,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        ksk: Generic_KS_Key,
+        torus_base: ::libc::c_int,
+    );
+}
+extern "C" {
+    pub fn full_domain_functional_bootstrap_KS21_2(
+        out: TLWE,
+        tv: TorusPolynomial,
+        in_: TLWE,
+        key: Bootstrap_Key,
+        ksk: Generic_KS_Key,
+        torus_base: ::libc::c_int,
+    );
+}
 extern "C" {
     pub fn multivalue_bootstrap_UBR_phase1(
         out: *mut TRGSW_DFT,
@@ -2304,3 +2404,9 @@ extern "C" {
 extern "C" {
     pub fn free_bootstrap_key_ga(key: Bootstrap_GA_Key);
 }
+extern "C" {
+    pub fn save_bootstrap_key_ga(fd: *mut FILE, key: Bootstrap_GA_Key);
+}
+extern "C" {
+    pub fn load_new_bootstrap_key_ga(fd: *mut FILE) -> Bootstrap_GA_Key;
+}
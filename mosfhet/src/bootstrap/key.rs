@@ -0,0 +1,207 @@
+use crate::common::macros::*;
+use crate::error::check;
+use crate::tlwe::TlweKey;
+use crate::trgsw::TrgswKey;
+use crate::Result;
+
+// inverse_mod_2N in misc.c looks up odd inverses mod 2N in tables built only
+// for these ring sizes, and silently reads the wrong table for any other
+const GA_UPPER_NS: [u32; 5] = [256, 512, 1024, 2048, 4096];
+
+#[repr(transparent)]
+pub struct BootstrapKey {
+    ptr: mosfhet_sys::Bootstrap_Key,
+}
+
+impl BootstrapKey {
+    pub fn new(
+        from_key: &TlweKey,
+        into_key: &TrgswKey,
+        unfolding: u32,
+    ) -> Self {
+        Self::try_new(from_key, into_key, unfolding).unwrap()
+    }
+
+    pub fn try_new(
+        from_key: &TlweKey,
+        into_key: &TrgswKey,
+        unfolding: u32,
+    ) -> Result<Self> {
+        let lower_n = from_key.lower_n();
+        let valid = unfolding > 0 && lower_n.is_multiple_of(unfolding);
+        check(valid, "unfolding", unfolding)?;
+        let ptr = unsafe {
+            mosfhet_sys::new_bootstrap_key(
+                into_key.as_ptr() as *mut _,
+                from_key.as_ptr() as *mut _,
+                unfolding as i32,
+            )
+        };
+        Ok(Self { ptr })
+    }
+
+    pub fn lower_n(&self) -> u32 {
+        unsafe { (*self.ptr).n as u32 }
+    }
+
+    pub fn k(&self) -> u32 {
+        unsafe { (*self.ptr).k as u32 }
+    }
+
+    pub fn upper_n(&self) -> u32 {
+        unsafe { (*self.ptr).N as u32 }
+    }
+
+    pub fn l(&self) -> u32 {
+        unsafe { (*self.ptr).l as u32 }
+    }
+
+    pub fn bg_bit(&self) -> u32 {
+        unsafe { (*self.ptr).Bg_bit as u32 }
+    }
+
+    pub fn unfolding(&self) -> u32 {
+        unsafe { (*self.ptr).unfolding as u32 }
+    }
+}
+
+impl_load!(BootstrapKey => load_new_bootstrap_key; lower_n, k, upper_n, l, bg_bit, unfolding);
+impl_save!(BootstrapKey => save_bootstrap_key(lower_n, k, upper_n, l, bg_bit, unfolding));
+impl_serde!(BootstrapKey);
+impl_drop!(BootstrapKey => free_bootstrap_key);
+impl_ptrs!(BootstrapKey);
+
+unsafe impl Send for BootstrapKey {}
+unsafe impl Sync for BootstrapKey {}
+
+#[repr(transparent)]
+pub struct BootstrapGaKey {
+    ptr: mosfhet_sys::Bootstrap_GA_Key,
+}
+
+impl BootstrapGaKey {
+    pub fn new(from_key: &TlweKey, into_key: &TrgswKey) -> Self {
+        Self::try_new(from_key, into_key).unwrap()
+    }
+
+    pub fn try_new(from_key: &TlweKey, into_key: &TrgswKey) -> Result<Self> {
+        let upper_n = into_key.upper_n();
+        let valid = GA_UPPER_NS.contains(&upper_n);
+        check(valid, "upper_n", upper_n)?;
+        let ptr = unsafe {
+            mosfhet_sys::new_bootstrap_key_ga(
+                into_key.as_ptr() as *mut _,
+                from_key.as_ptr() as *mut _,
+            )
+        };
+        Ok(Self { ptr })
+    }
+
+    pub fn lower_n(&self) -> u32 {
+        unsafe { (*self.ptr).n as u32 }
+    }
+
+    pub fn k(&self) -> u32 {
+        unsafe { (*self.ptr).k as u32 }
+    }
+
+    pub fn upper_n(&self) -> u32 {
+        unsafe { (*self.ptr).N as u32 }
+    }
+
+    pub fn l(&self) -> u32 {
+        unsafe { (*self.ptr).l as u32 }
+    }
+
+    pub fn bg_bit(&self) -> u32 {
+        unsafe { (*self.ptr).Bg_bit as u32 }
+    }
+
+    pub fn unfolding(&self) -> u32 {
+        unsafe { (*self.ptr).unfolding as u32 }
+    }
+}
+
+impl_load!(BootstrapGaKey => load_new_bootstrap_key_ga; lower_n, k, upper_n, l, bg_bit, unfolding);
+impl_save!(BootstrapGaKey => save_bootstrap_key_ga(lower_n, k, upper_n, l, bg_bit, unfolding));
+impl_serde!(BootstrapGaKey);
+impl_drop!(BootstrapGaKey => free_bootstrap_key_ga);
+impl_ptrs!(BootstrapGaKey);
+
+unsafe impl Send for BootstrapGaKey {}
+unsafe impl Sync for BootstrapGaKey {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trlwe::TrlweKey;
+
+    #[test]
+    fn new_load_save() {
+        let lower_n = 64;
+        let sigma_1 = 3.0517578125e-05;
+        let upper_n = 1024;
+        let sigma_2 = 2.9802322387695312e-08;
+        let k = 1;
+        let l = 2;
+        let bg_bit = 8;
+        let path = "/tmp/__bootstrap_key";
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        for unfolding in [1, 2] {
+            let key1 = BootstrapKey::new(&tlwe_key, &trgsw_key, unfolding);
+            key1.save(path).unwrap();
+            let key2 = BootstrapKey::load(path).unwrap();
+            std::fs::remove_file(path).unwrap();
+            assert_eq!(key1.lower_n(), key2.lower_n());
+            assert_eq!(key1.k(), key2.k());
+            assert_eq!(key1.upper_n(), key2.upper_n());
+            assert_eq!(key1.l(), key2.l());
+            assert_eq!(key1.bg_bit(), key2.bg_bit());
+            assert_eq!(key1.unfolding(), key2.unfolding());
+        }
+    }
+
+    #[test]
+    fn ga_new_load_save() {
+        let lower_n = 64;
+        let sigma_1 = 3.0517578125e-05;
+        let upper_n = 256;
+        let sigma_2 = 2.9802322387695312e-08;
+        let k = 1;
+        let l = 2;
+        let bg_bit = 8;
+        let path = "/tmp/__bootstrap_ga_key";
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let key1 = BootstrapGaKey::new(&tlwe_key, &trgsw_key);
+        key1.save(path).unwrap();
+        let key2 = BootstrapGaKey::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(key1.lower_n(), key2.lower_n());
+        assert_eq!(key1.k(), key2.k());
+        assert_eq!(key1.upper_n(), key2.upper_n());
+        assert_eq!(key1.l(), key2.l());
+        assert_eq!(key1.bg_bit(), key2.bg_bit());
+        assert_eq!(key1.unfolding(), key2.unfolding());
+    }
+
+    #[test]
+    fn try_new_invalid_unfolding() {
+        let sigma = 3.0517578125e-05;
+        let tlwe_key = TlweKey::new_binary(63, sigma);
+        let trlwe_key = TrlweKey::new(256, 1, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 3, 7);
+        for unfolding in [0, 2] {
+            assert!(matches!(
+                BootstrapKey::try_new(&tlwe_key, &trgsw_key, unfolding),
+                Err(crate::Error::InvalidParameter {
+                    name: "unfolding",
+                    ..
+                })
+            ));
+        }
+    }
+}
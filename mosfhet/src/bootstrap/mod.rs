@@ -0,0 +1,5 @@
+mod key;
+mod multivalue;
+
+pub use key::*;
+pub use multivalue::*;
@@ -0,0 +1,283 @@
+use crate::bootstrap::BootstrapKey;
+use crate::tlwe::{Tlwe, TlweArray};
+use crate::trgsw::TrgswDftArray;
+use crate::trlwe::{Trlwe, TrlweArray};
+
+pub struct MultiValueBootstrap<'a> {
+    key: &'a BootstrapKey,
+    input: Tlwe,
+    selectors: TrgswDftArray,
+}
+
+impl<'a> MultiValueBootstrap<'a> {
+    pub fn new(input: &Tlwe, key: &'a BootstrapKey) -> Self {
+        let unfolding = key.unfolding();
+        assert!(unfolding > 1);
+        assert_eq!(input.lower_n(), key.lower_n());
+        let len = (key.lower_n() / unfolding) as usize;
+        let mut selectors = unsafe {
+            TrgswDftArray::new_uninit(
+                len,
+                key.l(),
+                key.bg_bit(),
+                key.k(),
+                key.upper_n(),
+            )
+        };
+        unsafe {
+            mosfhet_sys::multivalue_bootstrap_UBR_phase1(
+                selectors.as_ptr_mut() as *mut _,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+            )
+        }
+        let input = input.clone();
+        Self {
+            key,
+            input,
+            selectors,
+        }
+    }
+
+    pub fn eval_from(
+        &self,
+        output: &mut Tlwe,
+        test_vector: &Trlwe,
+        torus_base: u32,
+    ) {
+        assert!(torus_base > 0);
+        assert_eq!(test_vector.k(), self.key.k());
+        assert_eq!(test_vector.upper_n(), self.key.upper_n());
+        assert_eq!(output.lower_n(), self.key.k() * self.key.upper_n());
+        unsafe {
+            mosfhet_sys::multivalue_bootstrap_UBR_phase2(
+                output.as_ptr_mut() as *mut _,
+                test_vector.as_ptr() as *mut _,
+                self.input.as_ptr() as *mut _,
+                self.selectors.as_ptr() as *mut _,
+                self.key.as_ptr() as *mut _,
+                torus_base as i32,
+            )
+        }
+    }
+
+    pub fn eval(&self, test_vector: &Trlwe, torus_base: u32) -> Tlwe {
+        let lower_n = self.key.k() * self.key.upper_n();
+        let mut output = unsafe { Tlwe::new_uninit(lower_n) };
+        self.eval_from(&mut output, test_vector, torus_base);
+        output
+    }
+
+    pub fn eval_all(
+        &self,
+        test_vectors: &TrlweArray,
+        torus_base: u32,
+    ) -> TlweArray {
+        let len = test_vectors.len();
+        let lower_n = self.key.k() * self.key.upper_n();
+        let mut output = unsafe { TlweArray::new_uninit(len, lower_n) };
+        output
+            .iter_mut()
+            .zip(test_vectors.iter())
+            .for_each(|(o, tv)| self.eval_from(o, tv, torus_base));
+        output
+    }
+}
+
+pub struct IntMultiValueBootstrap {
+    rotated: TrlweArray,
+    torus_base: u32,
+}
+
+impl IntMultiValueBootstrap {
+    pub fn new(input: &Tlwe, key: &BootstrapKey, torus_base: u32) -> Self {
+        assert!(torus_base.is_power_of_two());
+        assert_eq!(input.lower_n(), key.lower_n());
+        let len = torus_base as usize + 1;
+        let mut rotated =
+            unsafe { TrlweArray::new_uninit(len, key.k(), key.upper_n()) };
+        unsafe {
+            mosfhet_sys::multivalue_bootstrap_phase1(
+                rotated.as_ptr_mut() as *mut _,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+                torus_base as i32,
+            )
+        }
+        Self {
+            rotated,
+            torus_base,
+        }
+    }
+
+    pub fn torus_base(&self) -> u32 {
+        self.torus_base
+    }
+
+    pub fn eval_from(&self, output: &mut Tlwe, lut: &[u32]) {
+        let torus_base = self.torus_base;
+        assert_eq!(lut.len(), torus_base as usize);
+        assert!(lut.iter().all(|&v| v < torus_base));
+        let lower_n = self.rotated.k() * self.rotated.upper_n();
+        assert_eq!(output.lower_n(), lower_n);
+        let mut lut = lut.iter().map(|&v| v as i32).collect::<Vec<_>>();
+        unsafe {
+            mosfhet_sys::multivalue_bootstrap_phase2(
+                output.as_ptr_mut() as *mut _,
+                lut.as_mut_ptr(),
+                self.rotated.as_ptr() as *mut _,
+                torus_base as i32,
+                torus_base.ilog2() as i32,
+            )
+        }
+    }
+
+    pub fn eval(&self, lut: &[u32]) -> Tlwe {
+        let lower_n = self.rotated.k() * self.rotated.upper_n();
+        let mut output = unsafe { Tlwe::new_uninit(lower_n) };
+        self.eval_from(&mut output, lut);
+        output
+    }
+
+    pub fn eval_all<L: AsRef<[u32]>>(&self, luts: &[L]) -> TlweArray {
+        let lower_n = self.rotated.k() * self.rotated.upper_n();
+        let mut output = unsafe { TlweArray::new_uninit(luts.len(), lower_n) };
+        output
+            .iter_mut()
+            .zip(luts.iter())
+            .for_each(|(o, lut)| self.eval_from(o, lut.as_ref()));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{RawTorus, Torus};
+    use crate::poly::TorusPolynomial;
+    use crate::tlwe::TlweKey;
+    use crate::trgsw::TrgswKey;
+    use crate::trlwe::TrlweKey;
+
+    struct Keys {
+        tlwe_key: TlweKey,
+        tlwe_key_out: TlweKey,
+        trgsw_key: TrgswKey,
+        _trlwe_key: TrlweKey,
+    }
+
+    fn gen_keys() -> Keys {
+        let lower_n = 744;
+        let sigma_1 = 7.747831515176779e-06;
+        let upper_n = 2048;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 1;
+        let bg_bit = 23;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        Keys {
+            tlwe_key,
+            tlwe_key_out,
+            trgsw_key,
+            _trlwe_key: trlwe_key,
+        }
+    }
+
+    #[test]
+    fn multi_value_bootstrap() {
+        let keys = gen_keys();
+        let upper_n = keys.trgsw_key.upper_n();
+        let k = keys.trgsw_key.k();
+        let bk_key = BootstrapKey::new(&keys.tlwe_key, &keys.trgsw_key, 2);
+        let torus_base = 4;
+        let log_scale = 4;
+        let num_luts = 3;
+        let slot_size = (upper_n / torus_base) as usize;
+        let f = |j: RawTorus, x: RawTorus| (x * (j + 1) + j) % 16;
+        let tvs = TrlweArray::from_fn_noiseless(num_luts, k, upper_n, |j| {
+            TorusPolynomial::from_fn(upper_n, |i| {
+                let x = (i / slot_size) as RawTorus;
+                Torus::from_unsigned(f(j as RawTorus, x), log_scale)
+            })
+        });
+        for x in 0..torus_base as RawTorus {
+            let m = Torus::from_unsigned(x, 3);
+            let tlwe = Tlwe::new(m, &keys.tlwe_key);
+            let r = tlwe.multi_value_bootstrap(&tvs, &bk_key, torus_base);
+            assert_eq!(r.len(), num_luts);
+            for (j, v) in r.iter().enumerate() {
+                assert_eq!(
+                    v.phase(&keys.tlwe_key_out).into_unsigned(log_scale),
+                    f(j as RawTorus, x)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn int_multi_value_bootstrap() {
+        let keys = gen_keys();
+        let bk_key = BootstrapKey::new(&keys.tlwe_key, &keys.trgsw_key, 1);
+        let torus_base = 4;
+        let luts = [[1, 2, 3, 0], [3, 3, 0, 1], [0, 1, 2, 3]];
+        for x in 0..torus_base {
+            let m = Torus::from_unsigned(x as RawTorus, 3);
+            let tlwe = Tlwe::new(m, &keys.tlwe_key);
+            let mvb = IntMultiValueBootstrap::new(&tlwe, &bk_key, torus_base);
+            let r = mvb.eval_all(&luts);
+            for (v, lut) in r.iter().zip(luts.iter()) {
+                assert_eq!(
+                    v.phase(&keys.tlwe_key_out).into_unsigned(3),
+                    lut[x as usize] as RawTorus
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn packed_bootstrap() {
+        let keys = gen_keys();
+        let upper_n = keys.trgsw_key.upper_n();
+        let k = keys.trgsw_key.k();
+        let bk_key = BootstrapKey::new(&keys.tlwe_key, &keys.trgsw_key, 1);
+        let torus_base = 4;
+        let log_scale = 4;
+        let num_luts = 3;
+        let slot_size = upper_n as usize / (torus_base as usize * num_luts);
+        let f = |j: RawTorus, x: RawTorus| (x * (j + 1) + j) % 16;
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            let slot = i / slot_size;
+            let x = (slot / num_luts) as RawTorus;
+            let j = (slot % num_luts) as RawTorus;
+            Torus::from_unsigned(f(j, x), log_scale)
+        });
+        let tv = Trlwe::new_noiseless(poly, k, upper_n);
+        for x in 0..torus_base as RawTorus {
+            let m = Torus::from_unsigned(x, 3);
+            let tlwe = Tlwe::new(m, &keys.tlwe_key);
+            let r = TlweArray::from_packed_bootstrap(
+                &tlwe, &tv, &bk_key, torus_base, num_luts,
+            );
+            for (j, v) in r.iter().enumerate() {
+                assert_eq!(
+                    v.phase(&keys.tlwe_key_out).into_unsigned(log_scale),
+                    f(j as RawTorus, x)
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn int_multi_value_bootstrap_smaller_output() {
+        let keys = gen_keys();
+        let bk_key = BootstrapKey::new(&keys.tlwe_key, &keys.trgsw_key, 1);
+        let tlwe = Tlwe::new(Torus::MIN, &keys.tlwe_key);
+        let mvb = IntMultiValueBootstrap::new(&tlwe, &bk_key, 4);
+        let mut output = Tlwe::zero(&keys.tlwe_key);
+        mvb.eval_from(&mut output, &[0, 1, 2, 3]);
+    }
+}
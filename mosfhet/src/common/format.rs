@@ -0,0 +1,305 @@
+use std::io::{self, Read, Write};
+
+use libc::FILE;
+
+use crate::common::io::{read_u32, read_with, write_u32};
+use crate::common::RawTorus;
+use crate::{Error, Result};
+
+// Every object is stored as a container with the following layout, with all
+// integers in little-endian order:
+//
+//   magic    [u8; 4]   "MOSF"
+//   version  u16       format version
+//   tag      u16       object type
+//   torus    u16       torus width in bits
+//   count    u16       number of parameters
+//   params   [u64]     parameters needed to interpret the payload
+//   length   u64       payload length in bytes
+//   payload  [u8]      object data, as written by MOSFHET
+//   checksum u32       CRC-32 of everything above
+const MAGIC: [u8; 4] = *b"MOSF";
+const VERSION: u16 = 1;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u16)]
+pub(crate) enum Tag {
+    TlweKey = 1,
+    TlweKSKey = 2,
+    Tlwe = 3,
+    TlweArray = 4,
+    TrlweKey = 5,
+    TrlweKSKey = 6,
+    TrlwePKSKey = 7,
+    RelinearizationKey = 8,
+    TrlwePrivKSKey = 9,
+    TrlwePacking1KSKey = 10,
+    TrlweLutPackingKSKey = 11,
+    TrlweTracePackingKSKey = 12,
+    AutomorphismKeySet = 13,
+    Trlwe = 14,
+    TrlweDft = 15,
+    TrlweArray = 16,
+    TrgswKey = 17,
+    Trgsw = 18,
+    TrgswDft = 19,
+    TrgswArray = 20,
+    TrgswDftArray = 21,
+    BootstrapKey = 22,
+    BootstrapGaKey = 23,
+    GateKey = 24,
+    IntegerKey = 25,
+    RadixInteger = 26,
+    EncryptedLut = 27,
+}
+
+pub(crate) fn write_object<W, F>(
+    writer: &mut W,
+    tag: Tag,
+    params: &[u64],
+    f: F,
+) -> Result<()>
+where
+    W: Write + ?Sized,
+    F: FnOnce(&mut Vec<u8>) -> Result<()>,
+{
+    let mut payload = Vec::new();
+    f(&mut payload)?;
+    let mut header = Vec::with_capacity(20 + 8 * params.len());
+    header.extend_from_slice(&MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&(tag as u16).to_le_bytes());
+    header.extend_from_slice(&(RawTorus::BITS as u16).to_le_bytes());
+    header.extend_from_slice(&(params.len() as u16).to_le_bytes());
+    for param in params {
+        header.extend_from_slice(&param.to_le_bytes());
+    }
+    header.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    writer.write_all(&header)?;
+    writer.write_all(&payload)?;
+    write_u32(writer, crc32(crc32(0, &header), &payload))?;
+    Ok(())
+}
+
+pub(crate) fn read_object<R, T, F>(reader: &mut R, tag: Tag, f: F) -> Result<T>
+where
+    R: Read + ?Sized,
+    F: FnOnce(&[u64], &mut &[u8]) -> Result<T>,
+{
+    let mut header = vec![0; 12];
+    reader.read_exact(&mut header)?;
+    let field = |i: usize| u16::from_le_bytes([header[i], header[i + 1]]);
+    if header[..4] != MAGIC {
+        return Err(invalid("not a MOSFHET object"));
+    }
+    if field(4) != VERSION {
+        let version = field(4);
+        return Err(invalid(format!("unsupported format version {version}")));
+    }
+    if field(6) != tag as u16 {
+        let found = field(6);
+        return Err(invalid(format!("expected {tag:?}, found tag {found}")));
+    }
+    if field(8) as u32 != RawTorus::BITS {
+        let bits = field(8);
+        return Err(invalid(format!(
+            "expected {}-bit torus, found {bits}-bit torus",
+            RawTorus::BITS,
+        )));
+    }
+    let count = field(10) as usize;
+    header.resize(12 + 8 * count + 8, 0);
+    reader.read_exact(&mut header[12..])?;
+    let mut words = header[12..]
+        .chunks_exact(8)
+        .map(|w| u64::from_le_bytes(w.try_into().unwrap()));
+    let params: Vec<u64> = words.by_ref().take(count).collect();
+    let length = words.next().unwrap();
+    let mut payload = Vec::new();
+    reader.take(length).read_to_end(&mut payload)?;
+    if payload.len() as u64 != length {
+        return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+    }
+    if read_u32(reader)? != crc32(crc32(0, &header), &payload) {
+        return Err(invalid("checksum mismatch"));
+    }
+    let mut rest = payload.as_slice();
+    let output = f(&params, &mut rest)?;
+    if !rest.is_empty() {
+        return Err(invalid("trailing data after payload"));
+    }
+    Ok(output)
+}
+
+pub(crate) fn params<const N: usize>(params: &[u64]) -> Result<[u64; N]> {
+    params.try_into().map_err(|_| {
+        invalid(format!("expected {N} parameters, found {}", params.len()))
+    })
+}
+
+pub(crate) fn param<T: TryFrom<u64>>(value: u64) -> Result<T> {
+    T::try_from(value)
+        .map_err(|_| invalid(format!("parameter {value} out of range")))
+}
+
+// Dimensions are passed to MOSFHET as int, and none of them may be zero
+pub(crate) fn dim<T: TryFrom<u64>>(value: u64) -> Result<T> {
+    if value == 0 || i32::try_from(value).is_err() {
+        return Err(invalid(format!("dimension {value} out of range")));
+    }
+    param(value)
+}
+
+// Size of a sample made of the product of counts words of type W
+pub(crate) fn bytes<W>(counts: &[usize]) -> Option<usize> {
+    counts
+        .iter()
+        .try_fold(size_of::<W>(), |acc, &n| acc.checked_mul(n))
+}
+
+// MOSFHET allocates from the dimensions before reading, so the payload must
+// be checked to hold exactly len samples first
+pub(crate) fn check_payload(
+    payload: &[u8],
+    len: usize,
+    sample_bytes: Option<usize>,
+) -> Result<()> {
+    let expected = sample_bytes.and_then(|size| size.checked_mul(len));
+    if expected != Some(payload.len()) {
+        let found = payload.len();
+        return Err(invalid(format!(
+            "payload of {found} bytes does not hold {len} samples"
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn parse<T, F>(payload: &mut &[u8], f: F) -> Result<T>
+where
+    F: FnOnce(*mut FILE) -> T,
+{
+    match read_with(payload, f) {
+        Ok(output) => Ok(output),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => {
+            Err(invalid("payload too short"))
+        }
+        Err(error) => Err(error.into()),
+    }
+}
+
+// Files written by save before the container format was introduced hold a
+// bare MOSFHET payload, which the caller must describe
+pub(crate) fn parse_raw<T, F>(bytes: &[u8], f: F) -> Result<T>
+where
+    F: FnOnce(*mut FILE) -> T,
+{
+    let mut rest = bytes;
+    let output = parse(&mut rest, f)?;
+    if !rest.is_empty() {
+        return Err(invalid("trailing data after payload"));
+    }
+    Ok(output)
+}
+
+pub(crate) fn check_param(name: &str, param: u64, found: u32) -> Result<()> {
+    if param != found as u64 {
+        return Err(invalid(format!(
+            "{name} is {found}, but the header says {param}"
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn invalid(reason: impl Into<String>) -> Error {
+    Error::MalformedFile(reason.into())
+}
+
+fn crc32(crc: u32, data: &[u8]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut c = i as u32;
+            let mut j = 0;
+            while j < 8 {
+                c = if c & 1 == 1 {
+                    0xedb88320 ^ (c >> 1)
+                } else {
+                    c >> 1
+                };
+                j += 1;
+            }
+            table[i] = c;
+            i += 1;
+        }
+        table
+    };
+    !data.iter().fold(!crc, |c, &b| {
+        TABLE[((c ^ b as u32) & 0xff) as usize] ^ (c >> 8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(params: &[u64], payload: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_object(&mut bytes, Tag::Tlwe, params, |output| {
+            output.extend_from_slice(payload);
+            Ok(())
+        })
+        .unwrap();
+        bytes
+    }
+
+    fn read(bytes: &[u8], tag: Tag) -> Result<(Vec<u64>, Vec<u8>)> {
+        read_object(&mut &bytes[..], tag, |params, payload| {
+            let output = (params.to_vec(), payload.to_vec());
+            *payload = &[];
+            Ok(output)
+        })
+    }
+
+    #[test]
+    fn checksum() {
+        assert_eq!(crc32(0, b"123456789"), 0xcbf43926);
+        assert_eq!(crc32(crc32(0, b"1234"), b"56789"), 0xcbf43926);
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = write(&[7, 1 << 40], b"payload");
+        assert_eq!(bytes.len(), 12 + 16 + 8 + 7 + 4);
+        let (params, payload) = read(&bytes, Tag::Tlwe).unwrap();
+        assert_eq!(params, [7, 1 << 40]);
+        assert_eq!(payload, b"payload");
+        let [a, b] = super::params(&params).unwrap();
+        assert_eq!(param::<u32>(a).unwrap(), 7);
+        assert!(param::<u32>(b).is_err());
+        assert!(super::params::<3>(&params).is_err());
+    }
+
+    #[test]
+    fn validation() {
+        let bytes = write(&[7], b"payload");
+        let malformed = |bytes: &[u8], tag| {
+            matches!(read(bytes, tag), Err(Error::MalformedFile(_)))
+        };
+        assert!(malformed(&bytes, Tag::Trlwe));
+        for i in [0, 4, 8, 14, 30] {
+            let mut corrupt = bytes.clone();
+            corrupt[i] ^= 1;
+            assert!(malformed(&corrupt, Tag::Tlwe));
+        }
+        let truncated = read(&bytes[..bytes.len() - 6], Tag::Tlwe);
+        match truncated.unwrap_err() {
+            Error::Io(error) => {
+                assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof)
+            }
+            error => panic!("unexpected error {error}"),
+        }
+        let leftover = read_object(&mut &bytes[..], Tag::Tlwe, |_, _| Ok(()));
+        assert!(matches!(leftover, Err(Error::MalformedFile(_))));
+    }
+}
@@ -0,0 +1,107 @@
+use std::io::{self, Read, Write};
+
+use libc::{c_char, c_void, size_t, FILE};
+
+// The C loaders do not check for short reads, so the payload is followed by
+// zeroes instead of leaving their headers uninitialized. Reading into them
+// is still reported as an unexpected end of file.
+const PADDING: usize = 4096;
+
+pub(crate) fn read_with<T, F>(input: &mut &[u8], f: F) -> io::Result<T>
+where
+    F: FnOnce(*mut FILE) -> T,
+{
+    let mut buffer = Vec::with_capacity(input.len() + PADDING);
+    buffer.extend_from_slice(input);
+    buffer.resize(input.len() + PADDING, 0);
+    let (output, position) = unsafe {
+        let ptr = buffer.as_mut_ptr() as *mut c_void;
+        let c_file = libc::fmemopen(ptr, buffer.len(), c"r".as_ptr());
+        if c_file.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        let output = f(c_file);
+        let position = libc::ftell(c_file);
+        libc::fclose(c_file);
+        (output, position)
+    };
+    match usize::try_from(position) {
+        Ok(position) if position <= input.len() => {
+            *input = &input[position..];
+            Ok(output)
+        }
+        Ok(_) => Err(io::ErrorKind::UnexpectedEof.into()),
+        Err(_) => Err(io::Error::last_os_error()),
+    }
+}
+
+pub(crate) fn write_with<W, F>(writer: &mut W, f: F) -> io::Result<()>
+where
+    W: Write + ?Sized,
+    F: FnOnce(*mut FILE),
+{
+    let mut ptr: *mut c_char = std::ptr::null_mut();
+    let mut size: size_t = 0;
+    unsafe {
+        let c_file = libc::open_memstream(&mut ptr, &mut size);
+        if c_file.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+        f(c_file);
+        let failed = libc::ferror(c_file) != 0;
+        let result = if libc::fclose(c_file) != 0 || failed {
+            Err(io::Error::other("failed to write to memory stream"))
+        } else {
+            let bytes = std::slice::from_raw_parts(ptr as *const u8, size);
+            writer.write_all(bytes)
+        };
+        libc::free(ptr as *mut c_void);
+        result
+    }
+}
+
+pub(crate) fn read_u32<R: Read + ?Sized>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+pub(crate) fn write_u32<W: Write + ?Sized>(
+    writer: &mut W,
+    value: u32,
+) -> io::Result<()> {
+    writer.write_all(&value.to_le_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn round_trip() {
+        let mut bytes = Vec::new();
+        write_with(&mut bytes, |c_file| unsafe {
+            let data = [1u8, 2, 3, 4, 5];
+            libc::fwrite(data.as_ptr() as *const _, 1, 5, c_file);
+        })
+        .unwrap();
+        write_u32(&mut bytes, 0xdeadbeef).unwrap();
+        assert_eq!(bytes.len(), 9);
+        let mut reader = bytes.as_slice();
+        let data = read_with(&mut reader, |c_file| unsafe {
+            let mut data = [0u8; 5];
+            libc::fread(data.as_mut_ptr() as *mut _, 1, 5, c_file);
+            data
+        })
+        .unwrap();
+        assert_eq!(data, [1, 2, 3, 4, 5]);
+        assert_eq!(read_u32(&mut reader).unwrap(), 0xdeadbeef);
+        let result = read_with(&mut reader, |c_file| unsafe {
+            let mut data = [0xffu8; 4];
+            libc::fread(data.as_mut_ptr() as *mut _, 1, 4, c_file);
+            data
+        });
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
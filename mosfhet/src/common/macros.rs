@@ -7,6 +7,42 @@ macro_rules! impl_load {
         => $l:ident ($($ai:ident : $at:ty),* $(,)?)) => {
         impl_load!($ty<> => $l ($($ai : $at),*));
     };
+    ($ty:ident
+        => $l:ident; $($ci:ident),+ $(,)?) => {
+        impl $ty {
+            pub fn read_from(
+                reader: &mut impl std::io::Read,
+            ) -> crate::Result<Self> {
+                use crate::common::format::{self, Tag};
+                format::read_object(reader, Tag::$ty, |params, payload| {
+                    let [$($ci),*] = format::params(params)?;
+                    let output = format::parse(payload, |c_file| {
+                        let ptr = unsafe { mosfhet_sys::$l(c_file as *mut _) };
+                        Self { ptr }
+                    })?;
+                    $(format::check_param(stringify!($ci), $ci, output.$ci())?;)*
+                    Ok(output)
+                })
+            }
+
+            pub fn load(
+                path: impl AsRef<std::path::Path>,
+            ) -> crate::Result<Self> {
+                let file = std::fs::File::open(path)?;
+                Self::read_from(&mut std::io::BufReader::new(file))
+            }
+
+            pub fn load_raw(
+                path: impl AsRef<std::path::Path>,
+            ) -> crate::Result<Self> {
+                let bytes = std::fs::read(path)?;
+                crate::common::format::parse_raw(&bytes, |c_file| {
+                    let ptr = unsafe { mosfhet_sys::$l(c_file as *mut _) };
+                    Self { ptr }
+                })
+            }
+        }
+    };
     ($ty:ident <$($N:ident $(: $b0:ident $(+$b:ident)*)?),* $(,)?>
         => $l:ident) => {
         impl_load!($ty<$($N $(: $b0 $(+$b)*)?),*> => $l ());
@@ -14,25 +50,46 @@ macro_rules! impl_load {
     ($ty:ident <$($N:ident $(: $b0:ident $(+$b:ident)*)?),* $(,)?>
         => $l:ident ($($ai:ident : $at:ty),* $(,)?)) => {
         impl<$($N $(: $b0 $(+$b)*)?),*> $ty<$($N),*> {
+            pub fn read_from(
+                reader: &mut impl std::io::Read,
+            ) -> crate::Result<Self> {
+                use crate::common::format::{self, Tag};
+                format::read_object(reader, Tag::$ty, |params, payload| {
+                    let [$($ai),*] = format::params(params)?;
+                    $(let $ai: $at = format::dim($ai)?;)*
+                    let size = Self::sample_bytes($($ai),*);
+                    format::check_payload(payload, 1, size)?;
+                    format::parse(payload, |c_file| {
+                        let ptr = unsafe {
+                            mosfhet_sys::$l(c_file as *mut _, $($ai as i32),*)
+                        };
+                        Self { ptr }
+                    })
+                })
+            }
+
             pub fn load(
                 path: impl AsRef<std::path::Path>,
-                $($ai : $at),*
-            ) -> std::io::Result<Self> {
-                use std::os::fd::IntoRawFd;
+            ) -> crate::Result<Self> {
                 let file = std::fs::File::open(path)?;
-                let ptr = unsafe {
-                    let o_read =
-                        std::ffi::CStr::from_bytes_with_nul_unchecked(b"r\0")
-                            .as_ptr();
-                    let c_file = libc::fdopen(file.into_raw_fd(), o_read);
-                    let ptr = mosfhet_sys::$l(
-                        c_file as *mut _,
-                        $($ai as i32),*
-                    );
-                    libc::fclose(c_file);
-                    ptr
-                };
-                Ok(Self { ptr })
+                Self::read_from(&mut std::io::BufReader::new(file))
+            }
+
+            pub fn load_raw(
+                path: impl AsRef<std::path::Path>,
+                $($ai: $at),*
+            ) -> crate::Result<Self> {
+                use crate::common::format;
+                let bytes = std::fs::read(path)?;
+                $(let $ai: $at = format::dim($ai as u64)?;)*
+                let size = Self::sample_bytes($($ai),*);
+                format::check_payload(&bytes, 1, size)?;
+                format::parse_raw(&bytes, |c_file| {
+                    let ptr = unsafe {
+                        mosfhet_sys::$l(c_file as *mut _, $($ai as i32),*)
+                    };
+                    Self { ptr }
+                })
             }
         }
     };
@@ -54,28 +111,103 @@ macro_rules! impl_load_array {
     ($ty:ident <$($N:ident $(: $b0:ident $(+$b:ident)*)?),* $(,)?>
         => $l:ident ($($ai:ident : $at:ty),* $(,)?)) => {
         impl<$($N $(: $b0 $(+$b)*)?),*> $ty<$($N),*> {
+            pub fn read_from(
+                reader: &mut impl std::io::Read,
+            ) -> crate::Result<Self> {
+                use crate::common::format::{self, Tag};
+                format::read_object(reader, Tag::$ty, |params, payload| {
+                    let [len, $($ai),*] = format::params(params)?;
+                    let len: usize = format::dim(len)?;
+                    $(let $ai: $at = format::dim($ai)?;)*
+                    let size = Self::sample_bytes($($ai),*);
+                    format::check_payload(payload, len, size)?;
+                    format::parse(payload, |c_file| unsafe {
+                        let mut output = Self::new_uninit(len, $($ai),*);
+                        for sample in output.as_slice_mut().iter_mut() {
+                            mosfhet_sys::$l(
+                                c_file as *mut _,
+                                sample.as_ptr() as *mut _,
+                            );
+                        }
+                        output
+                    })
+                })
+            }
+
             pub fn load(
                 path: impl AsRef<std::path::Path>,
-                len: usize,
-                $($ai : $at),*
-            ) -> std::io::Result<Self> {
-                use std::os::fd::IntoRawFd;
+            ) -> crate::Result<Self> {
                 let file = std::fs::File::open(path)?;
-                unsafe {
-                    let o_read =
-                        std::ffi::CStr::from_bytes_with_nul_unchecked(b"r\0")
-                            .as_ptr();
-                    let c_file = libc::fdopen(file.into_raw_fd(), o_read);
-                    let mut ptr = Self::new_uninit(len, $($ai),*);
-                    for sample in ptr.as_slice_mut().iter_mut() {
+                Self::read_from(&mut std::io::BufReader::new(file))
+            }
+
+            pub fn load_raw(
+                path: impl AsRef<std::path::Path>,
+                len: usize,
+                $($ai: $at),*
+            ) -> crate::Result<Self> {
+                use crate::common::format;
+                let bytes = std::fs::read(path)?;
+                let len: usize = format::dim(len as u64)?;
+                $(let $ai: $at = format::dim($ai as u64)?;)*
+                let size = Self::sample_bytes($($ai),*);
+                format::check_payload(&bytes, len, size)?;
+                format::parse_raw(&bytes, |c_file| unsafe {
+                    let mut output = Self::new_uninit(len, $($ai),*);
+                    for sample in output.as_slice_mut().iter_mut() {
                         mosfhet_sys::$l(
                             c_file as *mut _,
                             sample.as_ptr() as *mut _,
                         );
                     }
-                    libc::fclose(c_file);
-                    Ok(ptr)
-                }
+                    output
+                })
+            }
+        }
+    };
+}
+
+// Serialization for types made of other objects, which provide
+//
+//   fn params(&self) -> [u64; _]
+//   fn write_payload(&self, payload: &mut Vec<u8>) -> crate::Result<()>
+//   fn read_payload(params: &[u64], payload: &mut &[u8]) -> crate::Result<Self>
+macro_rules! impl_load_save {
+    ($ty:ident) => {
+        impl $ty {
+            pub fn read_from(
+                reader: &mut impl std::io::Read,
+            ) -> crate::Result<Self> {
+                use crate::common::format::{self, Tag};
+                format::read_object(reader, Tag::$ty, Self::read_payload)
+            }
+
+            pub fn write_to(
+                &self,
+                writer: &mut impl std::io::Write,
+            ) -> crate::Result<()> {
+                use crate::common::format::{self, Tag};
+                format::write_object(writer, Tag::$ty, &self.params(), |p| {
+                    self.write_payload(p)
+                })
+            }
+
+            pub fn load(
+                path: impl AsRef<std::path::Path>,
+            ) -> crate::Result<Self> {
+                let file = std::fs::File::open(path)?;
+                Self::read_from(&mut std::io::BufReader::new(file))
+            }
+
+            pub fn save(
+                &self,
+                path: impl AsRef<std::path::Path>,
+            ) -> crate::Result<()> {
+                use std::io::Write;
+                let file = std::fs::File::create(path)?;
+                let mut writer = std::io::BufWriter::new(file);
+                self.write_to(&mut writer)?;
+                Ok(writer.flush()?)
             }
         }
     };
@@ -83,27 +215,37 @@ macro_rules! impl_load_array {
 
 macro_rules! impl_save {
     ($ty:ident
-        => $l:ident) => {
-        impl_save!($ty<> => $l);
+        => $l:ident $(($($ai:ident),* $(,)?))?) => {
+        impl_save!($ty<> => $l $(($($ai),*))?);
     };
     ($ty:ident <$($N:ident $(: $b0:ident $(+$b:ident)*)?),*>
-        => $l:ident) => {
+        => $l:ident $(($($ai:ident),* $(,)?))?) => {
         impl<$($N $(: $b0 $(+$b)*)?),*> $ty<$($N),*> {
+            pub fn write_to(
+                &self,
+                writer: &mut impl std::io::Write,
+            ) -> crate::Result<()> {
+                use crate::common::format::{self, Tag};
+                use crate::common::io::write_with;
+                let params = [$($(self.$ai() as u64),*)?];
+                format::write_object(writer, Tag::$ty, &params, |payload| {
+                    write_with(payload, |c_file| unsafe {
+                        let ptr = self.as_ptr() as *mut _;
+                        mosfhet_sys::$l(c_file as *mut _, ptr);
+                    })?;
+                    Ok(())
+                })
+            }
+
             pub fn save(
                 &self,
                 path: impl AsRef<std::path::Path>,
-            ) -> std::io::Result<()> {
-                use std::os::fd::IntoRawFd;
+            ) -> crate::Result<()> {
+                use std::io::Write;
                 let file = std::fs::File::create(path)?;
-                unsafe {
-                    let o_write =
-                        std::ffi::CStr::from_bytes_with_nul_unchecked(b"w\0")
-                            .as_ptr();
-                    let c_file = libc::fdopen(file.into_raw_fd(), o_write);
-                    mosfhet_sys::$l(c_file as *mut _, self.as_ptr() as *mut _);
-                    libc::fclose(c_file);
-                }
-                Ok(())
+                let mut writer = std::io::BufWriter::new(file);
+                self.write_to(&mut writer)?;
+                Ok(writer.flush()?)
             }
         }
     };
@@ -111,29 +253,66 @@ macro_rules! impl_save {
 
 macro_rules! impl_save_array {
     ($ty:ident
-        => $l:ident) => {
-        impl_save_array!($ty<> => $l);
+        => $l:ident $(($($ai:ident),* $(,)?))?) => {
+        impl_save_array!($ty<> => $l $(($($ai),*))?);
     };
     ($ty:ident <$($N:ident $(: $b0:ident $(+$b:ident)*)?),*>
-        => $l:ident) => {
+        => $l:ident $(($($ai:ident),* $(,)?))?) => {
         impl<$($N $(: $b0 $(+$b)*)?),*> $ty<$($N),*> {
+            pub fn write_to(
+                &self,
+                writer: &mut impl std::io::Write,
+            ) -> crate::Result<()> {
+                use crate::common::format::{self, Tag};
+                use crate::common::io::write_with;
+                let params = [self.len() as u64, $($(self.$ai() as u64),*)?];
+                format::write_object(writer, Tag::$ty, &params, |payload| {
+                    write_with(payload, |c_file| unsafe {
+                        for sample in self.as_slice().iter() {
+                            mosfhet_sys::$l(c_file as *mut _, sample.ptr);
+                        }
+                    })?;
+                    Ok(())
+                })
+            }
+
             pub fn save(
                 &self,
                 path: impl AsRef<std::path::Path>,
-            ) -> std::io::Result<()> {
-                use std::os::fd::IntoRawFd;
+            ) -> crate::Result<()> {
+                use std::io::Write;
                 let file = std::fs::File::create(path)?;
-                unsafe {
-                    let o_write =
-                        std::ffi::CStr::from_bytes_with_nul_unchecked(b"w\0")
-                            .as_ptr();
-                    let c_file = libc::fdopen(file.into_raw_fd(), o_write);
-                    for sample in self.as_slice().iter() {
-                        mosfhet_sys::$l(c_file as *mut _, sample.ptr);
-                    }
-                    libc::fclose(c_file);
-                }
-                Ok(())
+                let mut writer = std::io::BufWriter::new(file);
+                self.write_to(&mut writer)?;
+                Ok(writer.flush()?)
+            }
+        }
+    };
+}
+
+macro_rules! impl_serde {
+    ($ty:ident) => {
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $ty {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> std::result::Result<S::Ok, S::Error> {
+                use serde::ser::Error;
+                let mut bytes = Vec::new();
+                self.write_to(&mut bytes).map_err(S::Error::custom)?;
+                bytes.serialize(serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(
+                deserializer: D,
+            ) -> std::result::Result<Self, D::Error> {
+                use serde::de::Error;
+                let bytes = <Vec<u8>>::deserialize(deserializer)?;
+                Self::read_from(&mut bytes.as_slice()).map_err(D::Error::custom)
             }
         }
     };
@@ -223,7 +402,9 @@ pub(crate) use impl_drop;
 pub(crate) use impl_drop_array;
 pub(crate) use impl_load;
 pub(crate) use impl_load_array;
+pub(crate) use impl_load_save;
 pub(crate) use impl_ptrs;
 pub(crate) use impl_save;
 pub(crate) use impl_save_array;
+pub(crate) use impl_serde;
 pub(crate) use impl_slice_array;
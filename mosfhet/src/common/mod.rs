@@ -1,3 +1,5 @@
+pub(crate) mod format;
+pub(crate) mod io;
 pub(crate) mod macros;
 
 mod torus;
@@ -1,6 +1,7 @@
 use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 pub type RawTorus = mosfhet_sys::Torus;
+pub type Integer = mosfhet_sys::Integer;
 
 #[repr(transparent)]
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd)]
@@ -0,0 +1,119 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum Error {
+    ParameterMismatch {
+        name: &'static str,
+        expected: u64,
+        found: u64,
+    },
+    InvalidParameter {
+        name: &'static str,
+        value: u64,
+    },
+    InvalidScale {
+        scale: u64,
+        bound: u64,
+    },
+    InvalidSigma {
+        sigma: f64,
+    },
+    InsufficientSecurity {
+        bits: f64,
+        required: f64,
+    },
+    MalformedFile(String),
+    Io(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ParameterMismatch {
+                name,
+                expected,
+                found,
+            } => {
+                write!(f, "{name} mismatch: expected {expected}, found {found}")
+            }
+            Self::InvalidParameter { name, value } => {
+                write!(f, "invalid {name}: {value}")
+            }
+            Self::InvalidScale { scale, bound } => {
+                write!(f, "invalid scale {scale}, must be at most {bound}")
+            }
+            Self::InvalidSigma { sigma } => {
+                write!(f, "invalid sigma {sigma}, must be between 0 and 1")
+            }
+            Self::InsufficientSecurity { bits, required } => write!(
+                f,
+                "estimated security of {bits:.1} bits, required {required} bits"
+            ),
+            Self::MalformedFile(reason) => {
+                write!(f, "malformed file: {reason}")
+            }
+            Self::Io(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+pub(crate) fn check_eq<T: Into<u64>>(
+    name: &'static str,
+    expected: T,
+    found: T,
+) -> Result<()> {
+    let (expected, found) = (expected.into(), found.into());
+    if expected != found {
+        return Err(Error::ParameterMismatch {
+            name,
+            expected,
+            found,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn check<T: Into<u64>>(
+    valid: bool,
+    name: &'static str,
+    value: T,
+) -> Result<()> {
+    if !valid {
+        let value = value.into();
+        return Err(Error::InvalidParameter { name, value });
+    }
+    Ok(())
+}
+
+pub(crate) fn check_sigma(sigma: f64) -> Result<()> {
+    if !(sigma > 0.0 && sigma < 1.0) {
+        return Err(Error::InvalidSigma { sigma });
+    }
+    Ok(())
+}
+
+pub(crate) fn check_scale<T: Into<u64>>(scale: T, bound: T) -> Result<()> {
+    let (scale, bound) = (scale.into(), bound.into());
+    if scale > bound {
+        return Err(Error::InvalidScale { scale, bound });
+    }
+    Ok(())
+}
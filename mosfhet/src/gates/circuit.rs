@@ -0,0 +1,274 @@
+use rayon::prelude::*;
+
+use crate::gates::GateKey;
+use crate::tlwe::{Tlwe, TlweArray};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Wire(usize);
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Gate {
+    Input(usize),
+    Constant(bool),
+    Not(Wire),
+    And(Wire, Wire),
+    Nand(Wire, Wire),
+    Or(Wire, Wire),
+    Nor(Wire, Wire),
+    Xor(Wire, Wire),
+    Xnor(Wire, Wire),
+    Mux(Wire, Wire, Wire),
+}
+
+impl Gate {
+    fn operands(&self) -> Vec<Wire> {
+        match *self {
+            Gate::Input(_) | Gate::Constant(_) => vec![],
+            Gate::Not(a) => vec![a],
+            Gate::And(a, b)
+            | Gate::Nand(a, b)
+            | Gate::Or(a, b)
+            | Gate::Nor(a, b)
+            | Gate::Xor(a, b)
+            | Gate::Xnor(a, b) => vec![a, b],
+            Gate::Mux(s, a, b) => vec![s, a, b],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Circuit {
+    gates: Vec<Gate>,
+    num_inputs: usize,
+    outputs: Vec<Wire>,
+}
+
+impl Circuit {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn num_outputs(&self) -> usize {
+        self.outputs.len()
+    }
+
+    pub fn gates(&self) -> &[Gate] {
+        &self.gates
+    }
+
+    pub fn input(&mut self) -> Wire {
+        let index = self.num_inputs;
+        self.num_inputs += 1;
+        self.push(Gate::Input(index))
+    }
+
+    pub fn constant(&mut self, bit: bool) -> Wire {
+        self.push(Gate::Constant(bit))
+    }
+
+    pub fn not(&mut self, a: Wire) -> Wire {
+        self.push(Gate::Not(a))
+    }
+
+    pub fn and(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::And(a, b))
+    }
+
+    pub fn nand(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Nand(a, b))
+    }
+
+    pub fn or(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Or(a, b))
+    }
+
+    pub fn nor(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Nor(a, b))
+    }
+
+    pub fn xor(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Xor(a, b))
+    }
+
+    pub fn xnor(&mut self, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Xnor(a, b))
+    }
+
+    pub fn mux(&mut self, s: Wire, a: Wire, b: Wire) -> Wire {
+        self.push(Gate::Mux(s, a, b))
+    }
+
+    pub fn output(&mut self, wire: Wire) {
+        assert!(wire.0 < self.gates.len());
+        self.outputs.push(wire);
+    }
+
+    pub fn eval_plain(&self, inputs: &[bool]) -> Vec<bool> {
+        assert_eq!(inputs.len(), self.num_inputs);
+        let mut values: Vec<bool> = Vec::with_capacity(self.gates.len());
+        for gate in self.gates.iter() {
+            let v = |w: Wire| values[w.0];
+            let value = match *gate {
+                Gate::Input(i) => inputs[i],
+                Gate::Constant(bit) => bit,
+                Gate::Not(a) => !v(a),
+                Gate::And(a, b) => v(a) & v(b),
+                Gate::Nand(a, b) => !(v(a) & v(b)),
+                Gate::Or(a, b) => v(a) | v(b),
+                Gate::Nor(a, b) => !(v(a) | v(b)),
+                Gate::Xor(a, b) => v(a) ^ v(b),
+                Gate::Xnor(a, b) => !(v(a) ^ v(b)),
+                Gate::Mux(s, a, b) => {
+                    if v(s) {
+                        v(a)
+                    } else {
+                        v(b)
+                    }
+                }
+            };
+            values.push(value);
+        }
+        self.outputs.iter().map(|w| values[w.0]).collect()
+    }
+
+    pub fn eval(&self, key: &GateKey, inputs: &TlweArray) -> TlweArray {
+        assert_eq!(inputs.len(), self.num_inputs);
+        if !inputs.is_empty() {
+            assert_eq!(inputs.lower_n(), key.lower_n());
+        }
+        let mut values: Vec<Option<Tlwe>> = vec![None; self.gates.len()];
+        for layer in self.layers() {
+            let results = layer
+                .par_iter()
+                .map(|&i| {
+                    let v = |w: Wire| values[w.0].as_ref().unwrap();
+                    match self.gates[i] {
+                        Gate::Input(j) => inputs[j].clone(),
+                        Gate::Constant(bit) => key.constant(bit),
+                        Gate::Not(a) => key.not(v(a)),
+                        Gate::And(a, b) => key.and(v(a), v(b)),
+                        Gate::Nand(a, b) => key.nand(v(a), v(b)),
+                        Gate::Or(a, b) => key.or(v(a), v(b)),
+                        Gate::Nor(a, b) => key.nor(v(a), v(b)),
+                        Gate::Xor(a, b) => key.xor(v(a), v(b)),
+                        Gate::Xnor(a, b) => key.xnor(v(a), v(b)),
+                        Gate::Mux(s, a, b) => key.mux(v(s), v(a), v(b)),
+                    }
+                })
+                .collect::<Vec<_>>();
+            for (&i, value) in layer.iter().zip(results) {
+                values[i] = Some(value);
+            }
+        }
+        let len = self.outputs.len();
+        let mut output = unsafe { TlweArray::new_uninit(len, key.lower_n()) };
+        output
+            .iter_mut()
+            .zip(self.outputs.iter())
+            .for_each(|(o, w)| o.clone_from(values[w.0].as_ref().unwrap()));
+        output
+    }
+
+    fn push(&mut self, gate: Gate) -> Wire {
+        let index = self.gates.len();
+        assert!(gate.operands().iter().all(|w| w.0 < index));
+        self.gates.push(gate);
+        Wire(index)
+    }
+
+    fn layers(&self) -> Vec<Vec<usize>> {
+        let mut depths = Vec::with_capacity(self.gates.len());
+        let mut layers: Vec<Vec<usize>> = vec![];
+        for (i, gate) in self.gates.iter().enumerate() {
+            let depth = gate
+                .operands()
+                .iter()
+                .map(|w| depths[w.0] + 1)
+                .max()
+                .unwrap_or(0);
+            if layers.len() <= depth {
+                layers.resize(depth + 1, vec![]);
+            }
+            layers[depth].push(i);
+            depths.push(depth);
+        }
+        layers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bootstrap::BootstrapKey;
+    use crate::gates::{decode, encode};
+    use crate::tlwe::{TlweKSKey, TlweKey};
+    use crate::trgsw::TrgswKey;
+    use crate::trlwe::TrlweKey;
+
+    #[test]
+    fn circuit() {
+        let lower_n = 256;
+        let sigma_1 = 9.5367431640625e-07;
+        let upper_n = 1024;
+        let sigma_2 = 9.094947017729282e-13;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 7;
+        let t = 4;
+        let base_bit = 3;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let ks_key = TlweKSKey::new(&tlwe_key_out, &tlwe_key, t, base_bit);
+        let gate_key = GateKey::new(bk_key, ks_key);
+
+        // 2-bit comparator: a > b
+        let mut circuit = Circuit::new();
+        let a = [circuit.input(), circuit.input()];
+        let b = [circuit.input(), circuit.input()];
+        let nb1 = circuit.not(b[1]);
+        let gt1 = circuit.and(a[1], nb1);
+        let eq1 = circuit.xnor(a[1], b[1]);
+        let nb0 = circuit.not(b[0]);
+        let gt0 = circuit.and(a[0], nb0);
+        let gt0 = circuit.and(eq1, gt0);
+        let gt = circuit.or(gt1, gt0);
+        let one = circuit.constant(true);
+        let lsb = circuit.mux(gt, a[0], b[0]);
+        circuit.output(gt);
+        circuit.output(lsb);
+        circuit.output(one);
+        assert_eq!(circuit.num_inputs(), 4);
+        assert_eq!(circuit.num_outputs(), 3);
+
+        for x in 0..4usize {
+            for y in 0..4usize {
+                let bits = [x & 1, x >> 1, y & 1, y >> 1].map(|v| v == 1);
+                let inputs =
+                    TlweArray::from_fn(4, &tlwe_key, |i| encode(bits[i]));
+                let outputs = circuit.eval(&gate_key, &inputs);
+                let expected = circuit.eval_plain(&bits);
+                assert_eq!(expected[0], x > y);
+                for (o, e) in outputs.iter().zip(expected) {
+                    assert_eq!(decode(o.phase(&tlwe_key)), e);
+                }
+            }
+        }
+
+        // Circuits made only of constants take no inputs
+        let mut circuit = Circuit::new();
+        let zero = circuit.constant(false);
+        let one = circuit.constant(true);
+        let x = circuit.xor(zero, one);
+        circuit.output(x);
+        let inputs = TlweArray::zeroed(0, &tlwe_key);
+        let outputs = circuit.eval(&gate_key, &inputs);
+        assert!(decode(outputs[0].phase(&tlwe_key)));
+    }
+}
@@ -0,0 +1,182 @@
+use crate::bootstrap::BootstrapKey;
+use crate::common::format;
+use crate::common::macros::*;
+use crate::common::{RawTorus, Torus};
+use crate::error::check_eq;
+use crate::poly::TorusPolynomial;
+use crate::tlwe::{Tlwe, TlweKSKey};
+use crate::trlwe::Trlwe;
+use crate::Result;
+
+pub fn encode(bit: bool) -> Torus {
+    Torus::from_unsigned(bit as RawTorus, 2)
+}
+
+pub fn decode(m: Torus) -> bool {
+    m.into_unsigned(2) == 1
+}
+
+pub struct GateKey {
+    bootstrap_key: BootstrapKey,
+    ks_key: TlweKSKey,
+}
+
+impl GateKey {
+    pub fn new(bootstrap_key: BootstrapKey, ks_key: TlweKSKey) -> Self {
+        let lower_n = bootstrap_key.lower_n();
+        let upper_n = bootstrap_key.upper_n();
+        let k = bootstrap_key.k();
+        assert_eq!(ks_key.in_lower_n(), k * upper_n);
+        assert_eq!(ks_key.out_lower_n(), lower_n);
+        Self {
+            bootstrap_key,
+            ks_key,
+        }
+    }
+
+    pub fn bootstrap_key(&self) -> &BootstrapKey {
+        &self.bootstrap_key
+    }
+
+    pub fn ks_key(&self) -> &TlweKSKey {
+        &self.ks_key
+    }
+
+    pub fn lower_n(&self) -> u32 {
+        self.bootstrap_key.lower_n()
+    }
+
+    fn params(&self) -> [u64; 0] {
+        []
+    }
+
+    fn read_payload(params: &[u64], payload: &mut &[u8]) -> Result<Self> {
+        let [] = format::params(params)?;
+        let bootstrap_key = BootstrapKey::read_from(payload)?;
+        let ks_key = TlweKSKey::read_from(payload)?;
+        let upper_n = bootstrap_key.k() * bootstrap_key.upper_n();
+        check_eq("in_lower_n", upper_n, ks_key.in_lower_n())?;
+        check_eq("out_lower_n", bootstrap_key.lower_n(), ks_key.out_lower_n())?;
+        Ok(Self::new(bootstrap_key, ks_key))
+    }
+
+    fn write_payload(&self, payload: &mut Vec<u8>) -> Result<()> {
+        self.bootstrap_key.write_to(payload)?;
+        self.ks_key.write_to(payload)
+    }
+
+    pub fn constant(&self, bit: bool) -> Tlwe {
+        Tlwe::new_noiseless(encode(bit), self.lower_n())
+    }
+
+    pub fn not(&self, a: &Tlwe) -> Tlwe {
+        let mut output = self.constant(true);
+        output.sub_assign(a);
+        output
+    }
+
+    pub fn and(&self, a: &Tlwe, b: &Tlwe) -> Tlwe {
+        self.bootstrap(&a.add(b), false, false)
+    }
+
+    pub fn nand(&self, a: &Tlwe, b: &Tlwe) -> Tlwe {
+        self.bootstrap(&a.add(b), true, true)
+    }
+
+    pub fn or(&self, a: &Tlwe, b: &Tlwe) -> Tlwe {
+        self.bootstrap(&a.add(b), false, true)
+    }
+
+    pub fn nor(&self, a: &Tlwe, b: &Tlwe) -> Tlwe {
+        self.bootstrap(&a.add(b), true, false)
+    }
+
+    pub fn xor(&self, a: &Tlwe, b: &Tlwe) -> Tlwe {
+        let sum = a.add(b);
+        self.bootstrap(&sum.add(&sum), false, false)
+    }
+
+    pub fn xnor(&self, a: &Tlwe, b: &Tlwe) -> Tlwe {
+        let sum = a.add(b);
+        self.bootstrap(&sum.add(&sum), true, false)
+    }
+
+    pub fn mux(&self, s: &Tlwe, a: &Tlwe, b: &Tlwe) -> Tlwe {
+        let mut output = self.bootstrap_raw(&s.add(a), false, false);
+        let t = self.bootstrap_raw(&self.not(s).add(b), false, false);
+        output.add_assign(&t);
+        let mut output = output.key_switch(&self.ks_key);
+        output.add_assign(&self.constant(true));
+        output
+    }
+
+    fn bootstrap(&self, input: &Tlwe, v0: bool, v1: bool) -> Tlwe {
+        let mut output =
+            self.bootstrap_raw(input, v0, v1).key_switch(&self.ks_key);
+        let eighth = Torus::from_unsigned(1, 3);
+        output.add_assign(&Tlwe::new_noiseless(eighth, self.lower_n()));
+        output
+    }
+
+    fn bootstrap_raw(&self, input: &Tlwe, v0: bool, v1: bool) -> Tlwe {
+        let k = self.bootstrap_key.k();
+        let upper_n = self.bootstrap_key.upper_n();
+        let half_n = (upper_n / 2) as usize;
+        let sign = |v: bool| Torus::from_unsigned(if v { 1 } else { 7 }, 3);
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            sign(if i < half_n { v0 } else { v1 })
+        });
+        let tv = Trlwe::new_noiseless(poly, k, upper_n);
+        input.functional_bootstrap(&tv, &self.bootstrap_key, 2)
+    }
+}
+
+impl_load_save!(GateKey);
+impl_serde!(GateKey);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tlwe::TlweKey;
+    use crate::trgsw::TrgswKey;
+    use crate::trlwe::TrlweKey;
+
+    #[test]
+    fn gates() {
+        let lower_n = 256;
+        let sigma_1 = 9.5367431640625e-07;
+        let upper_n = 1024;
+        let sigma_2 = 9.094947017729282e-13;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 7;
+        let t = 4;
+        let base_bit = 3;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let ks_key = TlweKSKey::new(&tlwe_key_out, &tlwe_key, t, base_bit);
+        let gate_key = GateKey::new(bk_key, ks_key);
+        let dec = |c: &Tlwe| decode(c.phase(&tlwe_key));
+        for a in [false, true] {
+            let ca = Tlwe::new(encode(a), &tlwe_key);
+            assert_eq!(dec(&gate_key.not(&ca)), !a);
+            for b in [false, true] {
+                let cb = Tlwe::new(encode(b), &tlwe_key);
+                assert_eq!(dec(&gate_key.and(&ca, &cb)), a & b);
+                assert_eq!(dec(&gate_key.nand(&ca, &cb)), !(a & b));
+                assert_eq!(dec(&gate_key.or(&ca, &cb)), a | b);
+                assert_eq!(dec(&gate_key.nor(&ca, &cb)), !(a | b));
+                assert_eq!(dec(&gate_key.xor(&ca, &cb)), a ^ b);
+                assert_eq!(dec(&gate_key.xnor(&ca, &cb)), !(a ^ b));
+                for s in [false, true] {
+                    let cs = Tlwe::new(encode(s), &tlwe_key);
+                    let r = gate_key.mux(&cs, &ca, &cb);
+                    assert_eq!(dec(&r), if s { a } else { b });
+                }
+            }
+        }
+    }
+}
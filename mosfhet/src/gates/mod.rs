@@ -0,0 +1,5 @@
+mod circuit;
+mod key;
+
+pub use circuit::*;
+pub use key::*;
@@ -0,0 +1,277 @@
+use std::cmp::Ordering;
+
+use crate::bootstrap::BootstrapKey;
+use crate::common::format;
+use crate::common::macros::*;
+use crate::common::RawTorus;
+use crate::error::{check, check_eq};
+use crate::integer::{encode, precision, RadixInteger};
+use crate::tlwe::{Tlwe, TlweKSKey};
+use crate::trlwe::Trlwe;
+use crate::Result;
+
+pub struct IntegerKey {
+    bootstrap_key: BootstrapKey,
+    ks_key: TlweKSKey,
+    log_base: u32,
+    digit_tv: Trlwe,
+    carry_tv: Trlwe,
+    borrow_tv: Trlwe,
+    sign_tv: Trlwe,
+    combine_tv: Trlwe,
+    block_gt_tv: Trlwe,
+    block_le_tv: Trlwe,
+    select_tv: Trlwe,
+}
+
+impl IntegerKey {
+    pub fn new(
+        bootstrap_key: BootstrapKey,
+        ks_key: TlweKSKey,
+        log_base: u32,
+    ) -> Self {
+        let lower_n = bootstrap_key.lower_n();
+        let upper_n = bootstrap_key.upper_n();
+        let k = bootstrap_key.k();
+        assert_eq!(ks_key.in_lower_n(), k * upper_n);
+        assert_eq!(ks_key.out_lower_n(), lower_n);
+        assert!(log_base > 0);
+        let torus_base = 1 << precision(log_base);
+        assert_eq!(upper_n % torus_base, 0);
+        let base = 1 << log_base;
+        let tv = |f: &dyn Fn(RawTorus) -> RawTorus| {
+            Trlwe::from_lut_fn(k, upper_n, torus_base, |x| {
+                encode(f(x as RawTorus), log_base)
+            })
+        };
+        let ordering = |o: Ordering| (o as i8 + 1) as RawTorus;
+        Self {
+            bootstrap_key,
+            ks_key,
+            log_base,
+            digit_tv: tv(&|x| x % base),
+            carry_tv: tv(&|x| x / base),
+            borrow_tv: tv(&|x| (x < base) as RawTorus),
+            sign_tv: tv(&|x| ordering(x.cmp(&(base - 1)))),
+            combine_tv: tv(&|x| ordering(x.cmp(&3))),
+            block_gt_tv: tv(&|x| if x == 2 { base } else { 0 }),
+            block_le_tv: tv(&|x| if x == 2 { 0 } else { base }),
+            select_tv: tv(&|x| if x < base { x } else { 0 }),
+        }
+    }
+
+    pub fn bootstrap_key(&self) -> &BootstrapKey {
+        &self.bootstrap_key
+    }
+
+    pub fn ks_key(&self) -> &TlweKSKey {
+        &self.ks_key
+    }
+
+    pub fn lower_n(&self) -> u32 {
+        self.bootstrap_key.lower_n()
+    }
+
+    pub fn log_base(&self) -> u32 {
+        self.log_base
+    }
+
+    fn params(&self) -> [u64; 1] {
+        [self.log_base as u64]
+    }
+
+    fn read_payload(params: &[u64], payload: &mut &[u8]) -> Result<Self> {
+        let [log_base] = format::params(params)?;
+        let log_base: u32 = format::param(log_base)?;
+        let bootstrap_key = BootstrapKey::read_from(payload)?;
+        let ks_key = TlweKSKey::read_from(payload)?;
+        let upper_n = bootstrap_key.k() * bootstrap_key.upper_n();
+        check_eq("in_lower_n", upper_n, ks_key.in_lower_n())?;
+        check_eq("out_lower_n", bootstrap_key.lower_n(), ks_key.out_lower_n())?;
+        let log_upper_n = bootstrap_key.upper_n().ilog2();
+        let valid = log_base > 0 && precision(log_base) <= log_upper_n;
+        check(valid, "log_base", log_base)?;
+        Ok(Self::new(bootstrap_key, ks_key, log_base))
+    }
+
+    fn write_payload(&self, payload: &mut Vec<u8>) -> Result<()> {
+        self.bootstrap_key.write_to(payload)?;
+        self.ks_key.write_to(payload)
+    }
+
+    pub fn constant(&self, value: RawTorus, num_digits: usize) -> RadixInteger {
+        RadixInteger::new_noiseless(
+            value,
+            num_digits,
+            self.log_base,
+            self.lower_n(),
+        )
+    }
+
+    pub fn add(&self, a: &RadixInteger, b: &RadixInteger) -> RadixInteger {
+        self.check_operands(a, b);
+        let num_digits = a.num_digits();
+        let mut output = self.constant(0, num_digits);
+        let mut carry = self.trivial(0);
+        for i in 0..num_digits {
+            let mut sum = a.digits[i].add(&b.digits[i]);
+            sum.add_assign(&carry);
+            output.digits[i].clone_from(&self.bootstrap(&sum, &self.digit_tv));
+            if i + 1 < num_digits {
+                carry = self.bootstrap(&sum, &self.carry_tv);
+            }
+        }
+        output
+    }
+
+    pub fn sub(&self, a: &RadixInteger, b: &RadixInteger) -> RadixInteger {
+        self.check_operands(a, b);
+        let num_digits = a.num_digits();
+        let mut output = self.constant(0, num_digits);
+        let mut borrow = self.trivial(0);
+        for i in 0..num_digits {
+            let mut diff = self.trivial(1 << self.log_base);
+            diff.add_assign(&a.digits[i]);
+            diff.sub_assign(&b.digits[i]);
+            diff.sub_assign(&borrow);
+            output.digits[i].clone_from(&self.bootstrap(&diff, &self.digit_tv));
+            if i + 1 < num_digits {
+                borrow = self.bootstrap(&diff, &self.borrow_tv);
+            }
+        }
+        output
+    }
+
+    pub fn compare(&self, a: &RadixInteger, b: &RadixInteger) -> Tlwe {
+        self.check_operands(a, b);
+        let offset = self.trivial((1 << self.log_base) - 1);
+        let mut result = self.trivial(1);
+        for i in 0..a.num_digits() {
+            let mut diff = a.digits[i].sub(&b.digits[i]);
+            diff.add_assign(&offset);
+            let sign = self.bootstrap(&diff, &self.sign_tv);
+            if i == 0 {
+                result = sign;
+            } else {
+                let mut combined = sign.add(&sign);
+                combined.add_assign(&result);
+                result = self.bootstrap(&combined, &self.combine_tv);
+            }
+        }
+        result
+    }
+
+    pub fn max(&self, a: &RadixInteger, b: &RadixInteger) -> RadixInteger {
+        let ordering = self.compare(b, a);
+        self.select(&ordering, b, a)
+    }
+
+    pub fn argmax(&self, values: &[RadixInteger]) -> RadixInteger {
+        assert!(!values.is_empty());
+        let last = (values.len() - 1) as RawTorus;
+        let bits = RawTorus::BITS - last.leading_zeros();
+        let num_digits = bits.div_ceil(self.log_base).max(1) as usize;
+        let mut max = values[0].clone();
+        let mut index = self.constant(0, num_digits);
+        for (i, value) in values.iter().enumerate().skip(1) {
+            let ordering = self.compare(value, &max);
+            let candidate = self.constant(i as RawTorus, num_digits);
+            index = self.select(&ordering, &candidate, &index);
+            max = self.select(&ordering, value, &max);
+        }
+        index
+    }
+
+    fn select(
+        &self,
+        ordering: &Tlwe,
+        a: &RadixInteger,
+        b: &RadixInteger,
+    ) -> RadixInteger {
+        self.check_operands(a, b);
+        let block_a = self.bootstrap(ordering, &self.block_le_tv);
+        let block_b = self.bootstrap(ordering, &self.block_gt_tv);
+        let mut output = self.constant(0, a.num_digits());
+        for i in 0..a.num_digits() {
+            let digit_a =
+                self.bootstrap(&a.digits[i].add(&block_a), &self.select_tv);
+            let digit_b =
+                self.bootstrap(&b.digits[i].add(&block_b), &self.select_tv);
+            output.digits[i].add_from(&digit_a, &digit_b);
+        }
+        output
+    }
+
+    fn trivial(&self, value: RawTorus) -> Tlwe {
+        Tlwe::new_noiseless(encode(value, self.log_base), self.lower_n())
+    }
+
+    fn bootstrap(&self, input: &Tlwe, test_vector: &Trlwe) -> Tlwe {
+        let torus_base = 1 << precision(self.log_base);
+        input
+            .functional_bootstrap(test_vector, &self.bootstrap_key, torus_base)
+            .key_switch(&self.ks_key)
+    }
+
+    fn check_operands(&self, a: &RadixInteger, b: &RadixInteger) {
+        assert_eq!(a.log_base(), self.log_base);
+        assert_eq!(b.log_base(), self.log_base);
+        assert_eq!(a.num_digits(), b.num_digits());
+        assert_eq!(a.lower_n(), self.lower_n());
+        assert_eq!(b.lower_n(), self.lower_n());
+    }
+}
+
+impl_load_save!(IntegerKey);
+impl_serde!(IntegerKey);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::decode_ordering;
+    use crate::tlwe::TlweKey;
+    use crate::trgsw::TrgswKey;
+    use crate::trlwe::TrlweKey;
+
+    #[test]
+    fn arithmetic() {
+        let lower_n = 256;
+        let sigma_1 = 9.5367431640625e-07;
+        let upper_n = 1024;
+        let sigma_2 = 9.094947017729282e-13;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 7;
+        let t = 4;
+        let base_bit = 3;
+        let log_base = 2;
+        let num_digits = 3;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let ks_key = TlweKSKey::new(&tlwe_key_out, &tlwe_key, t, base_bit);
+        let int_key = IntegerKey::new(bk_key, ks_key, log_base);
+        let enc = |x| RadixInteger::new(x, num_digits, log_base, &tlwe_key);
+        let modulus = 1 << (num_digits as u32 * log_base);
+        for (x, y) in [(0, 0), (13, 13), (27, 45), (63, 1), (40, 39)] {
+            let (a, b) = (enc(x), enc(y));
+            let r = int_key.add(&a, &b);
+            assert_eq!(r.decrypt(&tlwe_key), (x + y) % modulus);
+            let r = int_key.sub(&a, &b);
+            assert_eq!(r.decrypt(&tlwe_key), (x + modulus - y) % modulus);
+            let r = int_key.compare(&a, &b);
+            assert_eq!(
+                decode_ordering(r.phase(&tlwe_key), log_base),
+                x.cmp(&y)
+            );
+            let r = int_key.max(&a, &b);
+            assert_eq!(r.decrypt(&tlwe_key), x.max(y));
+        }
+        let values = [17, 42, 5, 42, 30];
+        let encrypted: Vec<_> = values.iter().map(|&x| enc(x)).collect();
+        let r = int_key.argmax(&encrypted);
+        assert_eq!(r.decrypt(&tlwe_key), 1);
+    }
+}
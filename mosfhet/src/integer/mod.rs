@@ -0,0 +1,5 @@
+mod key;
+mod radix;
+
+pub use key::*;
+pub use radix::*;
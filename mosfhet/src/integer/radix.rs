@@ -0,0 +1,155 @@
+use std::cmp::Ordering;
+
+use crate::common::format;
+use crate::common::macros::*;
+use crate::common::{RawTorus, Torus};
+use crate::error::check;
+use crate::tlwe::{TlweArray, TlweKey};
+use crate::Result;
+
+pub fn precision(log_base: u32) -> u32 {
+    (log_base + 1).max(3)
+}
+
+pub fn encode(value: RawTorus, log_base: u32) -> Torus {
+    Torus::from_unsigned(value, precision(log_base) as usize + 1)
+}
+
+pub fn decode(m: Torus, log_base: u32) -> RawTorus {
+    m.into_unsigned(precision(log_base) as usize + 1)
+}
+
+pub fn decode_ordering(m: Torus, log_base: u32) -> Ordering {
+    match decode(m, log_base) {
+        0 => Ordering::Less,
+        1 => Ordering::Equal,
+        _ => Ordering::Greater,
+    }
+}
+
+#[derive(Clone)]
+pub struct RadixInteger {
+    pub(crate) digits: TlweArray,
+    pub(crate) log_base: u32,
+}
+
+impl RadixInteger {
+    pub fn new(
+        value: RawTorus,
+        num_digits: usize,
+        log_base: u32,
+        key: &TlweKey,
+    ) -> Self {
+        Self::check_value(value, num_digits, log_base);
+        let mask = (1 << log_base) - 1;
+        let digits = TlweArray::from_fn(num_digits, key, |i| {
+            let digit = (value >> (i as u32 * log_base)) & mask;
+            encode(digit, log_base)
+        });
+        Self { digits, log_base }
+    }
+
+    pub fn new_noiseless(
+        value: RawTorus,
+        num_digits: usize,
+        log_base: u32,
+        lower_n: u32,
+    ) -> Self {
+        Self::check_value(value, num_digits, log_base);
+        let mask = (1 << log_base) - 1;
+        let digits = TlweArray::from_fn_noiseless(num_digits, lower_n, |i| {
+            let digit = (value >> (i as u32 * log_base)) & mask;
+            encode(digit, log_base)
+        });
+        Self { digits, log_base }
+    }
+
+    pub fn num_digits(&self) -> usize {
+        self.digits.len()
+    }
+
+    pub fn log_base(&self) -> u32 {
+        self.log_base
+    }
+
+    pub fn lower_n(&self) -> u32 {
+        self.digits.lower_n()
+    }
+
+    pub fn digits(&self) -> &TlweArray {
+        &self.digits
+    }
+
+    pub fn decrypt(&self, key: &TlweKey) -> RawTorus {
+        let mask = (1 << self.log_base) - 1;
+        self.digits.as_slice().iter().rev().fold(0, |acc, digit| {
+            let digit = decode(digit.phase(key), self.log_base) & mask;
+            (acc << self.log_base) | digit
+        })
+    }
+
+    fn params(&self) -> [u64; 1] {
+        [self.log_base as u64]
+    }
+
+    fn read_payload(params: &[u64], payload: &mut &[u8]) -> Result<Self> {
+        let [log_base] = format::params(params)?;
+        let log_base: u32 = format::param(log_base)?;
+        check(log_base > 0, "log_base", log_base)?;
+        let digits = TlweArray::read_from(payload)?;
+        Ok(Self { digits, log_base })
+    }
+
+    fn write_payload(&self, payload: &mut Vec<u8>) -> Result<()> {
+        self.digits.write_to(payload)
+    }
+
+    fn check_value(value: RawTorus, num_digits: usize, log_base: u32) {
+        assert!(log_base > 0 && num_digits > 0);
+        let bits = num_digits as u32 * log_base;
+        assert!(bits >= RawTorus::BITS || value >> bits == 0);
+    }
+}
+
+impl_load_save!(RadixInteger);
+impl_serde!(RadixInteger);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt() {
+        let lower_n = 256;
+        let sigma = 9.5367431640625e-07;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma);
+        for log_base in [1, 2, 3] {
+            for value in [0, 1, 37, 255] {
+                let x = RadixInteger::new(value, 8, log_base, &tlwe_key);
+                assert_eq!(x.num_digits(), 8);
+                assert_eq!(x.decrypt(&tlwe_key), value);
+                let x =
+                    RadixInteger::new_noiseless(value, 8, log_base, lower_n);
+                assert_eq!(x.decrypt(&tlwe_key), value);
+            }
+        }
+        assert_eq!(decode_ordering(encode(0, 2), 2), Ordering::Less);
+        assert_eq!(decode_ordering(encode(1, 2), 2), Ordering::Equal);
+        assert_eq!(decode_ordering(encode(2, 2), 2), Ordering::Greater);
+    }
+
+    #[test]
+    fn write_read() {
+        let lower_n = 256;
+        let sigma = 9.5367431640625e-07;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma);
+        let x = RadixInteger::new(201, 4, 2, &tlwe_key);
+        let mut bytes = Vec::new();
+        x.write_to(&mut bytes).unwrap();
+        let y = RadixInteger::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(y.num_digits(), 4);
+        assert_eq!(y.log_base(), 2);
+        assert_eq!(y.lower_n(), lower_n);
+        assert_eq!(y.decrypt(&tlwe_key), 201);
+    }
+}
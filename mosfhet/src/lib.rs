@@ -1,17 +1,40 @@
+pub mod bootstrap;
 pub mod common;
+mod error;
+pub mod gates;
+pub mod integer;
+pub mod lut;
+pub mod network;
+pub mod noise;
+pub mod params;
 pub mod poly;
 pub mod tlwe;
 pub mod trgsw;
 pub mod trlwe;
 
+pub use error::{Error, Result};
+
 pub mod prelude {
-    pub use crate::common::{RawTorus, Torus};
-    pub use crate::poly::{BinaryPolynomial, DftPolynomial, TorusPolynomial};
+    pub use crate::bootstrap::{
+        BootstrapGaKey, BootstrapKey, IntMultiValueBootstrap,
+        MultiValueBootstrap,
+    };
+    pub use crate::common::{Integer, RawTorus, Torus};
+    pub use crate::gates::{Circuit, GateKey, Wire};
+    pub use crate::integer::{IntegerKey, RadixInteger};
+    pub use crate::lut::{EncryptedLut, Padding, TestVector};
+    pub use crate::network::SwitchNetwork;
+    pub use crate::params::ParamSet;
+    pub use crate::poly::{
+        BinaryPolynomial, DftPolynomial, IntPolynomial, TorusPolynomial,
+    };
     pub use crate::tlwe::{Tlwe, TlweArray, TlweKSKey, TlweKey};
     pub use crate::trgsw::{
         Trgsw, TrgswArray, TrgswDft, TrgswDftArray, TrgswKey,
     };
     pub use crate::trlwe::{
-        Trlwe, TrlweArray, TrlweDft, TrlweKSKey, TrlweKey, TrlwePKSKey,
+        AutomorphismKeySet, RelinearizationKey, Trlwe, TrlweArray, TrlweDft,
+        TrlweKSKey, TrlweKey, TrlweLutPackingKSKey, TrlwePKSKey,
+        TrlwePacking1KSKey, TrlwePrivKSKey, TrlweTracePackingKSKey,
     };
 }
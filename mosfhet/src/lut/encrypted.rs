@@ -0,0 +1,507 @@
+use crate::common::format;
+use crate::common::macros::*;
+use crate::common::Torus;
+use crate::error::{check, check_eq};
+use crate::poly::TorusPolynomial;
+use crate::tlwe::Tlwe;
+use crate::trgsw::TrgswDftArray;
+use crate::trlwe::{Trlwe, TrlweArray, TrlweDft, TrlweKey, TrlwePacking1KSKey};
+use crate::Result;
+
+#[derive(Clone)]
+pub struct EncryptedLut {
+    luts: TrlweArray,
+    size: usize,
+}
+
+impl EncryptedLut {
+    pub fn new(luts: TrlweArray, size: usize) -> Self {
+        Self::try_new(luts, size).unwrap()
+    }
+
+    pub fn try_new(luts: TrlweArray, size: usize) -> Result<Self> {
+        check(size > 0, "size", size as u64)?;
+        let len = Self::num_polys(size, luts.upper_n());
+        check_eq("len", len as u64, luts.len() as u64)?;
+        Ok(Self { luts, size })
+    }
+
+    pub fn from_fn<F>(size: usize, key: &TrlweKey, f: F) -> Self
+    where
+        F: Fn(usize) -> Torus,
+    {
+        let upper_n = key.upper_n();
+        let len = Self::num_polys(size, upper_n);
+        let luts = TrlweArray::from_fn(len, key, |i| {
+            Self::poly_from_fn(size, upper_n, i, &f)
+        });
+        Self::new(luts, size)
+    }
+
+    pub fn from_fn_noiseless<F>(size: usize, k: u32, upper_n: u32, f: F) -> Self
+    where
+        F: Fn(usize) -> Torus,
+    {
+        let len = Self::num_polys(size, upper_n);
+        let luts = TrlweArray::from_fn_noiseless(len, k, upper_n, |i| {
+            Self::poly_from_fn(size, upper_n, i, &f)
+        });
+        Self::new(luts, size)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn address_bits(&self) -> usize {
+        self.size.next_power_of_two().ilog2() as usize
+    }
+
+    pub fn k(&self) -> u32 {
+        self.luts.k()
+    }
+
+    pub fn upper_n(&self) -> u32 {
+        self.luts.upper_n()
+    }
+
+    pub fn as_trlwe_array(&self) -> &TrlweArray {
+        &self.luts
+    }
+
+    pub fn into_trlwe_array(self) -> TrlweArray {
+        self.luts
+    }
+
+    fn params(&self) -> [u64; 1] {
+        [self.size as u64]
+    }
+
+    fn read_payload(params: &[u64], payload: &mut &[u8]) -> Result<Self> {
+        let [size] = format::params(params)?;
+        let size: usize = format::param(size)?;
+        let luts = TrlweArray::read_from(payload)?;
+        let len = Self::num_polys(size, luts.upper_n());
+        if size == 0 || luts.len() != len {
+            return Err(format::invalid("size does not match the luts"));
+        }
+        Ok(Self { luts, size })
+    }
+
+    fn write_payload(&self, payload: &mut Vec<u8>) -> Result<()> {
+        self.luts.write_to(payload)
+    }
+
+    pub fn read_into(&self, output: &mut Tlwe, address: &TrgswDftArray) {
+        self.try_read_into(output, address).unwrap()
+    }
+
+    pub fn try_read_into(
+        &self,
+        output: &mut Tlwe,
+        address: &TrgswDftArray,
+    ) -> Result<()> {
+        self.check_address(address)?;
+        self.try_read_offset_into(output, address, 0)
+    }
+
+    pub fn read(&self, address: &TrgswDftArray) -> Tlwe {
+        self.try_read(address).unwrap()
+    }
+
+    pub fn try_read(&self, address: &TrgswDftArray) -> Result<Tlwe> {
+        let lower_n = self.k() * self.upper_n();
+        let mut output = unsafe { Tlwe::new_uninit(lower_n) };
+        self.try_read_into(&mut output, address)?;
+        Ok(output)
+    }
+
+    // Reads entry offset + address, where the plaintext offset is aligned to
+    // the span of the address, so only the polynomials of that span are used
+    pub fn read_offset_into(
+        &self,
+        output: &mut Tlwe,
+        address: &TrgswDftArray,
+        offset: usize,
+    ) {
+        self.try_read_offset_into(output, address, offset).unwrap()
+    }
+
+    pub fn try_read_offset_into(
+        &self,
+        output: &mut Tlwe,
+        address: &TrgswDftArray,
+        offset: usize,
+    ) -> Result<()> {
+        let upper_n = self.upper_n();
+        let log_upper_n = upper_n.ilog2() as usize;
+        let bits = address.len();
+        check(bits <= self.address_bits(), "address_bits", bits as u64)?;
+        check_eq("k", self.k(), address.k())?;
+        check_eq("upper_n", upper_n, address.upper_n())?;
+        let aligned = offset.is_multiple_of(1 << bits);
+        check(aligned && offset < self.size, "offset", offset as u64)?;
+        check_eq("lower_n", self.k() * upper_n, output.lower_n())?;
+        let split = bits.min(log_upper_n);
+        let (lower, upper) = address.as_slice().split_at(split);
+        let start = offset / upper_n as usize;
+        let end = self.luts.len().min(start + (1 << upper.len()));
+        let mut luts = self.luts.as_slice()[start..end].to_vec();
+        for (i, bit) in upper.iter().enumerate() {
+            bit.cmux_vectored(luts.as_mut_slice(), 1 << i);
+        }
+        let lut = &mut luts[0];
+        for (i, bit) in lower.iter().enumerate() {
+            let rot = lut.mul_by_xai_pred(2 * upper_n - (1 << i));
+            let rot = Trlwe::from_dft(&TrlweDft::mul_trlwe_dft(&rot, bit));
+            lut.add_assign(&rot);
+        }
+        output.set_from_trlwe(lut, offset % upper_n as usize);
+        Ok(())
+    }
+
+    pub fn read_offset(&self, address: &TrgswDftArray, offset: usize) -> Tlwe {
+        self.try_read_offset(address, offset).unwrap()
+    }
+
+    pub fn try_read_offset(
+        &self,
+        address: &TrgswDftArray,
+        offset: usize,
+    ) -> Result<Tlwe> {
+        let lower_n = self.k() * self.upper_n();
+        let mut output = unsafe { Tlwe::new_uninit(lower_n) };
+        self.try_read_offset_into(&mut output, address, offset)?;
+        Ok(output)
+    }
+
+    pub fn increment(&mut self, address: &TrgswDftArray, amount: Torus) {
+        self.try_increment(address, amount).unwrap()
+    }
+
+    pub fn try_increment(
+        &mut self,
+        address: &TrgswDftArray,
+        amount: Torus,
+    ) -> Result<()> {
+        self.check_address(address)?;
+        let mask = self.scatter(address, self.monomial_mask(amount));
+        for (lut, mask) in self.luts.iter_mut().zip(mask.iter()) {
+            lut.add_assign(mask);
+        }
+        Ok(())
+    }
+
+    pub fn decrement(&mut self, address: &TrgswDftArray, amount: Torus) {
+        self.try_decrement(address, amount).unwrap()
+    }
+
+    pub fn try_decrement(
+        &mut self,
+        address: &TrgswDftArray,
+        amount: Torus,
+    ) -> Result<()> {
+        self.check_address(address)?;
+        let mask = self.scatter(address, self.monomial_mask(amount));
+        for (lut, mask) in self.luts.iter_mut().zip(mask.iter()) {
+            lut.sub_assign(mask);
+        }
+        Ok(())
+    }
+
+    pub fn add_assign(&mut self, rhs: &Self) {
+        assert_eq!(self.size, rhs.size);
+        assert_eq!(self.k(), rhs.k());
+        assert_eq!(self.upper_n(), rhs.upper_n());
+        for (lut, rhs) in self.luts.iter_mut().zip(rhs.luts.iter()) {
+            lut.add_assign(rhs);
+        }
+    }
+
+    pub fn write(
+        &mut self,
+        address: &TrgswDftArray,
+        value: Torus,
+        key: &TrlwePacking1KSKey,
+    ) {
+        self.try_write(address, value, key).unwrap()
+    }
+
+    pub fn try_write(
+        &mut self,
+        address: &TrgswDftArray,
+        value: Torus,
+        key: &TrlwePacking1KSKey,
+    ) -> Result<()> {
+        let lower_n = self.k() * self.upper_n();
+        check_eq("in_lower_n", lower_n, key.in_lower_n())?;
+        check_eq("k", self.k(), key.out_k())?;
+        check_eq("upper_n", self.upper_n(), key.out_upper_n())?;
+        let mut delta = Tlwe::new_noiseless(value, lower_n);
+        delta.sub_assign(&self.try_read(address)?);
+        let mask = Trlwe::from_tlwe_packing1(&delta, key);
+        let mask = self.scatter(address, mask);
+        for (lut, mask) in self.luts.iter_mut().zip(mask.iter()) {
+            lut.add_assign(mask);
+        }
+        Ok(())
+    }
+
+    fn check_address(&self, address: &TrgswDftArray) -> Result<()> {
+        let bits = self.address_bits() as u64;
+        check_eq("address_bits", bits, address.len() as u64)?;
+        check_eq("k", self.k(), address.k())?;
+        check_eq("upper_n", self.upper_n(), address.upper_n())
+    }
+
+    fn monomial_mask(&self, amount: Torus) -> Trlwe {
+        let upper_n = self.upper_n();
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            if i == 0 {
+                amount
+            } else {
+                Torus::MIN
+            }
+        });
+        Trlwe::new_noiseless(poly, self.k(), upper_n)
+    }
+
+    fn scatter(&self, address: &TrgswDftArray, mask: Trlwe) -> Vec<Trlwe> {
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let log_upper_n = upper_n.ilog2() as usize;
+        let split = address.len().min(log_upper_n);
+        let (lower, upper) = address.as_slice().split_at(split);
+        let mut masks = vec![mask];
+        masks.resize_with(self.luts.len(), || {
+            Trlwe::zeroed_noiseless(k, upper_n)
+        });
+        let head = &mut masks[0];
+        for (i, bit) in lower.iter().enumerate() {
+            let rot = head.mul_by_xai_pred(1 << i);
+            let rot = Trlwe::from_dft(&TrlweDft::mul_trlwe_dft(&rot, bit));
+            head.add_assign(&rot);
+        }
+        for (i, bit) in upper.iter().enumerate().rev() {
+            bit.cdemux_vectored(masks.as_mut_slice(), 1 << i);
+        }
+        masks
+    }
+
+    fn num_polys(size: usize, upper_n: u32) -> usize {
+        size.div_ceil(upper_n as usize)
+    }
+
+    fn poly_from_fn<F>(
+        size: usize,
+        upper_n: u32,
+        index: usize,
+        f: &F,
+    ) -> TorusPolynomial
+    where
+        F: Fn(usize) -> Torus,
+    {
+        TorusPolynomial::from_fn(upper_n, |j| {
+            let x = index * upper_n as usize + j;
+            if x < size {
+                f(x)
+            } else {
+                Torus::MIN
+            }
+        })
+    }
+}
+
+impl_load_save!(EncryptedLut);
+impl_serde!(EncryptedLut);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::RawTorus;
+    use crate::tlwe::TlweKey;
+    use crate::trgsw::TrgswKey;
+
+    #[test]
+    fn increment_write() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 10;
+        let precision = 6;
+        let size = 2 * upper_n as usize + 40;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let packing_ks_key =
+            TrlwePacking1KSKey::new(&tlwe_key, &trlwe_key, 6, 4);
+        let encode = |x: RawTorus| Torus::from_unsigned(x, precision);
+        let mut lut = EncryptedLut::from_fn_noiseless(size, k, upper_n, |x| {
+            encode(x as RawTorus % 8)
+        });
+        let mut expected: Vec<_> =
+            (0..size).map(|x| x as RawTorus % 8).collect();
+        let ops = [(3, 5, 0), (300, 2, 1), (530, 7, 2), (3, 1, 1), (77, 9, 2)];
+        for (x, amount, op) in ops {
+            let address =
+                TrgswDftArray::from_fn(lut.address_bits(), &trgsw_key, |i| {
+                    (Torus::from_raw((x as RawTorus >> i) & 1), 0)
+                });
+            match op {
+                0 => {
+                    lut.increment(&address, encode(amount));
+                    expected[x] += amount;
+                }
+                1 => {
+                    lut.decrement(&address, encode(amount));
+                    expected[x] -= amount;
+                }
+                _ => {
+                    lut.write(&address, encode(amount), &packing_ks_key);
+                    expected[x] = amount;
+                }
+            }
+        }
+        for (i, trlwe) in lut.as_trlwe_array().iter().enumerate() {
+            let phase = trlwe.phase(&trlwe_key);
+            for (j, v) in phase.iter().enumerate() {
+                let x = i * upper_n as usize + j;
+                let y = if x < size { expected[x] } else { 0 };
+                assert_eq!(v.into_unsigned(precision), y);
+            }
+        }
+    }
+
+    #[test]
+    fn add_assign() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let precision = 6;
+        let size = upper_n as usize + 40;
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let encode = |x: usize| Torus::from_unsigned(x as RawTorus, precision);
+        let mut lut =
+            EncryptedLut::from_fn(size, &trlwe_key, |x| encode(x % 7));
+        let other = EncryptedLut::from_fn(size, &trlwe_key, |x| encode(x % 5));
+        lut.add_assign(&other);
+        for (i, trlwe) in lut.as_trlwe_array().iter().enumerate() {
+            let phase = trlwe.phase(&trlwe_key);
+            for (j, v) in phase.iter().enumerate() {
+                let x = i * upper_n as usize + j;
+                let y = if x < size { x % 7 + x % 5 } else { 0 };
+                assert_eq!(v.into_unsigned(precision), y as RawTorus);
+            }
+        }
+    }
+
+    #[test]
+    fn read() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let l = 3;
+        let bg_bit = 10;
+        let precision = 6;
+        let f = |x: usize| {
+            let y = (x * 37 + 11) as RawTorus % (1 << precision);
+            Torus::from_unsigned(y, precision)
+        };
+        for k in [1, 2] {
+            let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+            let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+            let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+            for size in [100, 3 * upper_n as usize + 17] {
+                let lut = EncryptedLut::from_fn(size, &trlwe_key, f);
+                assert_eq!(lut.as_trlwe_array().len(), size.div_ceil(256));
+                for x in [0, 1, size / 2, size - 1] {
+                    let address = TrgswDftArray::from_fn(
+                        lut.address_bits(),
+                        &trgsw_key,
+                        |i| (Torus::from_raw((x as RawTorus >> i) & 1), 0),
+                    );
+                    let r = lut.read(&address);
+                    assert_eq!(
+                        r.phase(&tlwe_key).into_unsigned(precision),
+                        f(x).into_unsigned(precision)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn read_offset() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let precision = 6;
+        let size = 4 * upper_n as usize;
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 3, 10);
+        let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let f = |x: usize| {
+            let y = (x * 37 + 11) as RawTorus % (1 << precision);
+            Torus::from_unsigned(y, precision)
+        };
+        let lut = EncryptedLut::from_fn(size, &trlwe_key, f);
+        for (bits, offset, x) in [(3, 0, 5), (3, 296, 7), (9, 512, 300)] {
+            let address = TrgswDftArray::from_fn(bits, &trgsw_key, |i| {
+                (Torus::from_raw((x as RawTorus >> i) & 1), 0)
+            });
+            let r = lut.read_offset(&address, offset);
+            assert_eq!(
+                r.phase(&tlwe_key).into_unsigned(precision),
+                f(offset + x).into_unsigned(precision)
+            );
+        }
+        let address = TrgswDftArray::from_elem(3, &trgsw_key, Torus::MIN, 0);
+        assert!(matches!(
+            lut.try_read_offset(&address, 4),
+            Err(crate::Error::InvalidParameter { name: "offset", .. })
+        ));
+    }
+
+    #[test]
+    fn write_read() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let size = upper_n as usize + 17;
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let f = |x: usize| Torus::from_unsigned(x as RawTorus % 8, 4);
+        let lut = EncryptedLut::from_fn(size, &trlwe_key, f);
+        let mut bytes = Vec::new();
+        lut.write_to(&mut bytes).unwrap();
+        let other = EncryptedLut::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(other.size(), size);
+        assert_eq!((other.k(), other.upper_n()), (1, upper_n));
+        let luts = lut.as_trlwe_array().iter();
+        for (x, y) in luts.zip(other.as_trlwe_array().iter()) {
+            let (x, y) = (x.phase(&trlwe_key), y.phase(&trlwe_key));
+            assert_eq!(x.as_slice(), y.as_slice());
+        }
+        let mut reader = &bytes[..bytes.len() / 2];
+        assert!(EncryptedLut::read_from(&mut reader).is_err());
+    }
+
+    #[test]
+    fn try_read_mismatch() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 3, 10);
+        let lut =
+            EncryptedLut::from_fn_noiseless(600, 1, upper_n, |_| Torus::MIN);
+        let address = TrgswDftArray::from_elem(3, &trgsw_key, Torus::MIN, 0);
+        assert!(matches!(
+            lut.try_read(&address),
+            Err(crate::Error::ParameterMismatch {
+                name: "address_bits",
+                ..
+            })
+        ));
+        let luts = TrlweArray::zeroed_noiseless(2, 1, upper_n);
+        assert!(matches!(
+            EncryptedLut::try_new(luts, 600),
+            Err(crate::Error::ParameterMismatch { name: "len", .. })
+        ));
+    }
+}
@@ -0,0 +1,5 @@
+mod encrypted;
+mod test_vector;
+
+pub use encrypted::*;
+pub use test_vector::*;
@@ -0,0 +1,256 @@
+use crate::common::{RawTorus, Torus};
+use crate::error::check;
+use crate::poly::TorusPolynomial;
+use crate::trlwe::{Trlwe, TrlweArray};
+use crate::Result;
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Padding {
+    #[default]
+    Bit,
+    FullDomain,
+}
+
+#[derive(Clone, Debug)]
+pub struct TestVector {
+    functions: Vec<fn(RawTorus) -> RawTorus>,
+    input_precision: u32,
+    output_precision: u32,
+    padding: Padding,
+}
+
+impl TestVector {
+    pub fn new(f: fn(RawTorus) -> RawTorus) -> Self {
+        Self {
+            functions: vec![f],
+            input_precision: 1,
+            output_precision: 1,
+            padding: Padding::Bit,
+        }
+    }
+
+    pub fn with_fn(mut self, f: fn(RawTorus) -> RawTorus) -> Self {
+        self.functions.push(f);
+        self
+    }
+
+    pub fn with_input_precision(mut self, precision: u32) -> Self {
+        assert!(precision > 0 && precision < RawTorus::BITS);
+        assert!(precision > 1 || self.padding != Padding::FullDomain);
+        self.input_precision = precision;
+        self
+    }
+
+    pub fn with_output_precision(mut self, precision: u32) -> Self {
+        assert!(precision > 0 && precision < RawTorus::BITS);
+        self.output_precision = precision;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Padding) -> Self {
+        // Full-domain test vectors are built from half of the domain
+        assert!(self.input_precision > 1 || padding != Padding::FullDomain);
+        self.padding = padding;
+        self
+    }
+
+    pub fn num_luts(&self) -> usize {
+        self.functions.len()
+    }
+
+    pub fn input_precision(&self) -> u32 {
+        self.input_precision
+    }
+
+    pub fn output_precision(&self) -> u32 {
+        self.output_precision
+    }
+
+    pub fn padding(&self) -> Padding {
+        self.padding
+    }
+
+    pub fn domain_size(&self) -> usize {
+        1 << self.input_precision
+    }
+
+    pub fn torus_base(&self) -> u32 {
+        match self.padding {
+            Padding::Bit => 1 << self.input_precision,
+            Padding::FullDomain => 1 << (self.input_precision - 1),
+        }
+    }
+
+    pub fn eval(&self, index: usize, x: RawTorus) -> RawTorus {
+        assert!(x < self.domain_size() as RawTorus);
+        let y = self.functions[index](x);
+        assert!(y < 1 << self.output_precision);
+        y
+    }
+
+    pub fn encode(&self, x: RawTorus) -> Torus {
+        let log_scale = match self.padding {
+            Padding::Bit => self.input_precision + 1,
+            Padding::FullDomain => self.input_precision,
+        };
+        assert!(x < self.domain_size() as RawTorus);
+        Torus::from_unsigned(x, log_scale as usize)
+    }
+
+    pub fn decode(&self, phase: Torus) -> RawTorus {
+        phase.into_unsigned(self.output_precision as usize)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let bound = 1 << self.output_precision;
+        for f in &self.functions {
+            for x in 0..self.domain_size() as RawTorus {
+                let y = f(x);
+                check(y < bound, "output", y)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn polynomial(&self, upper_n: u32) -> TorusPolynomial {
+        assert_eq!(self.padding, Padding::Bit);
+        let num_luts = self.num_luts();
+        let slots = self.domain_size() * num_luts;
+        assert_eq!(upper_n as usize % slots, 0);
+        let slot_size = upper_n as usize / slots;
+        TorusPolynomial::from_fn(upper_n, |i| {
+            let slot = i / slot_size;
+            self.output(slot % num_luts, slot / num_luts)
+        })
+    }
+
+    pub fn trlwe(&self, k: u32, upper_n: u32) -> Trlwe {
+        match self.padding {
+            Padding::Bit => {
+                Trlwe::new_noiseless(self.polynomial(upper_n), k, upper_n)
+            }
+            Padding::FullDomain => {
+                assert_eq!(self.num_luts(), 1);
+                let precision = self.input_precision;
+                Trlwe::from_full_domain_lut_fn(k, upper_n, precision, |x| {
+                    self.output(0, x)
+                })
+            }
+        }
+    }
+
+    pub fn clot21_trlwes(&self, k: u32, upper_n: u32) -> TrlweArray {
+        assert_eq!(self.padding, Padding::FullDomain);
+        assert_eq!(self.num_luts(), 1);
+        let precision = self.input_precision;
+        TrlweArray::from_clot21_lut_fn(k, upper_n, precision, |x| {
+            self.output(0, x)
+        })
+    }
+
+    pub fn ks21_polynomial(&self, upper_n: u32) -> TorusPolynomial {
+        assert_eq!(self.padding, Padding::FullDomain);
+        assert_eq!(self.num_luts(), 1);
+        let torus_base = self.domain_size() as u32;
+        TorusPolynomial::from_ks21_lut_fn(upper_n, torus_base, |x| {
+            self.output(0, x)
+        })
+    }
+
+    fn output(&self, index: usize, x: usize) -> Torus {
+        let y = self.eval(index, x as RawTorus);
+        Torus::from_unsigned(y, self.output_precision as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bootstrap::BootstrapKey;
+    use crate::tlwe::{Tlwe, TlweArray, TlweKSKey, TlweKey};
+    use crate::trgsw::TrgswKey;
+    use crate::trlwe::TrlweKey;
+
+    #[test]
+    fn polynomial_layout() {
+        let tv = TestVector::new(|x| x + 1)
+            .with_fn(|x| 3 - x)
+            .with_input_precision(2)
+            .with_output_precision(3);
+        assert!(tv.validate().is_ok());
+        assert_eq!(tv.num_luts(), 2);
+        assert_eq!(tv.torus_base(), 4);
+        let poly = tv.polynomial(16);
+        let expected = [1, 3, 2, 2, 3, 1, 4, 0];
+        for (i, v) in poly.iter().enumerate() {
+            assert_eq!(v.into_unsigned(3), expected[i / 2]);
+        }
+    }
+
+    #[test]
+    fn validate_output_range() {
+        let tv = TestVector::new(|x| x * 4)
+            .with_input_precision(2)
+            .with_output_precision(3);
+        let result = tv.validate();
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidParameter { value: 8, .. })
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn full_domain_single_bit() {
+        TestVector::new(|x| x).with_padding(Padding::FullDomain);
+    }
+
+    #[test]
+    fn bootstrap() {
+        let lower_n = 64;
+        let sigma_1 = 3.0517578125e-05;
+        let upper_n = 1024;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 13;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let ks_key = TlweKSKey::new(&tlwe_key_out, &tlwe_key, 4, 4);
+        let tv = TestVector::new(|x| (x * x + 1) % 8)
+            .with_fn(|x| 7 - x)
+            .with_input_precision(2)
+            .with_output_precision(3);
+        let packed = tv.trlwe(k, upper_n);
+        let torus_base = tv.torus_base();
+        for x in 0..tv.domain_size() as RawTorus {
+            let tlwe = Tlwe::new(tv.encode(x), &tlwe_key);
+            let r = TlweArray::from_packed_bootstrap(
+                &tlwe,
+                &packed,
+                &bk_key,
+                torus_base,
+                tv.num_luts(),
+            );
+            for (i, r) in r.iter().enumerate() {
+                assert_eq!(tv.decode(r.phase(&tlwe_key_out)), tv.eval(i, x));
+            }
+        }
+        let tv = TestVector::new(|x| (x * x + 3) % 8)
+            .with_input_precision(3)
+            .with_output_precision(3)
+            .with_padding(Padding::FullDomain);
+        let full = tv.trlwe(k, upper_n);
+        let precision = tv.input_precision();
+        for x in 0..tv.domain_size() as RawTorus {
+            let tlwe = Tlwe::new(tv.encode(x), &tlwe_key);
+            let r = tlwe.full_domain_functional_bootstrap(
+                &full, &bk_key, &ks_key, precision,
+            );
+            assert_eq!(tv.decode(r.phase(&tlwe_key_out)), tv.eval(0, x));
+        }
+    }
+}
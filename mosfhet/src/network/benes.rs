@@ -0,0 +1,105 @@
+use super::SwitchNetwork;
+
+impl SwitchNetwork {
+    pub fn benes(width: usize) -> Self {
+        assert!(width > 1 && width.is_power_of_two());
+        let positions: Vec<usize> = (0..width).collect();
+        let mut switches = Vec::new();
+        benes_switches(&positions, &mut switches);
+        Self { width, switches }
+    }
+
+    pub fn benes_controls(perm: &[usize]) -> Vec<bool> {
+        let width = perm.len();
+        assert!(width > 1 && width.is_power_of_two());
+        let mut seen = vec![false; width];
+        for &x in perm.iter() {
+            assert!(x < width && !seen[x]);
+            seen[x] = true;
+        }
+        let mut controls = Vec::new();
+        benes_route(perm, &mut controls);
+        controls
+    }
+}
+
+fn benes_switches(positions: &[usize], switches: &mut Vec<(usize, usize)>) {
+    if positions.len() == 2 {
+        switches.push((positions[0], positions[1]));
+        return;
+    }
+    let pairs = positions.chunks_exact(2).map(|p| (p[0], p[1]));
+    switches.extend(pairs.clone());
+    let upper: Vec<usize> = positions.iter().copied().step_by(2).collect();
+    let lower: Vec<usize> =
+        positions.iter().copied().skip(1).step_by(2).collect();
+    benes_switches(&upper, switches);
+    benes_switches(&lower, switches);
+    switches.extend(pairs);
+}
+
+fn benes_route(perm: &[usize], controls: &mut Vec<bool>) {
+    let width = perm.len();
+    if width == 2 {
+        controls.push(perm[0] == 1);
+        return;
+    }
+    let mut inv = vec![0; width];
+    for (j, &x) in perm.iter().enumerate() {
+        inv[x] = j;
+    }
+    let mut upper = vec![None; width];
+    for start in 0..width {
+        if upper[start].is_some() {
+            continue;
+        }
+        let mut j = start;
+        while upper[j].is_none() {
+            upper[j] = Some(true);
+            upper[j ^ 1] = Some(false);
+            j = inv[perm[j ^ 1] ^ 1];
+        }
+    }
+    let half = width / 2;
+    let mut first = vec![false; half];
+    let mut last = vec![false; half];
+    let mut upper_perm = vec![0; half];
+    let mut lower_perm = vec![0; half];
+    for (j, &x) in perm.iter().enumerate() {
+        let is_upper = upper[j] == Some(true);
+        if is_upper {
+            first[x / 2] = x % 2 == 1;
+            last[j / 2] = j % 2 == 1;
+            upper_perm[j / 2] = x / 2;
+        } else {
+            lower_perm[j / 2] = x / 2;
+        }
+    }
+    controls.extend(first);
+    benes_route(&upper_perm, controls);
+    benes_route(&lower_perm, controls);
+    controls.extend(last);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn benes_routing() {
+        for log_width in 1..6 {
+            let width = 1 << log_width;
+            let network = SwitchNetwork::benes(width);
+            assert_eq!(network.num_switches(), width * log_width - width / 2);
+            for seed in 0..16 {
+                let perm: Vec<usize> = (0..width)
+                    .map(|i| (i * (2 * seed + 1) + seed) % width)
+                    .collect();
+                let controls = SwitchNetwork::benes_controls(&perm);
+                let mut items: Vec<usize> = (0..width).collect();
+                network.apply_plain(&mut items, &controls);
+                assert_eq!(items, perm);
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+mod benes;
+mod sort;
+mod switch;
+
+pub use switch::*;
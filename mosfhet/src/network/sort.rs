@@ -0,0 +1,90 @@
+use super::SwitchNetwork;
+
+impl SwitchNetwork {
+    pub fn bitonic(width: usize) -> Self {
+        assert!(width > 1 && width.is_power_of_two());
+        let mut switches = Vec::new();
+        let mut k = 2;
+        while k <= width {
+            let mut j = k / 2;
+            while j > 0 {
+                for i in 0..width {
+                    let l = i ^ j;
+                    if l > i {
+                        if i & k == 0 {
+                            switches.push((i, l));
+                        } else {
+                            switches.push((l, i));
+                        }
+                    }
+                }
+                j /= 2;
+            }
+            k *= 2;
+        }
+        Self { width, switches }
+    }
+
+    pub fn odd_even_merge(width: usize) -> Self {
+        assert!(width > 1 && width.is_power_of_two());
+        let mut switches = Vec::new();
+        let mut p = 1;
+        while p < width {
+            let mut k = p;
+            while k > 0 {
+                let mut j = k % p;
+                while j + k < width {
+                    for i in 0..k.min(width - j - k) {
+                        if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                            switches.push((i + j, i + j + k));
+                        }
+                    }
+                    j += 2 * k;
+                }
+                k /= 2;
+            }
+            p *= 2;
+        }
+        Self { width, switches }
+    }
+
+    pub fn sort_controls<T: Ord + Clone>(&self, keys: &[T]) -> Vec<bool> {
+        assert_eq!(keys.len(), self.width);
+        let mut keys = keys.to_vec();
+        self.switches
+            .iter()
+            .map(|&(a, b)| {
+                let swap = keys[a] > keys[b];
+                if swap {
+                    keys.swap(a, b);
+                }
+                swap
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorting() {
+        for log_width in 1..4 {
+            let width = 1 << log_width;
+            let networks = [
+                SwitchNetwork::bitonic(width),
+                SwitchNetwork::odd_even_merge(width),
+            ];
+            for network in networks {
+                for bits in 0..(1u32 << width) {
+                    let mut keys: Vec<u32> =
+                        (0..width).map(|i| (bits >> i) & 1).collect();
+                    let controls = network.sort_controls(&keys);
+                    network.apply_plain(&mut keys, &controls);
+                    assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+                }
+            }
+        }
+    }
+}
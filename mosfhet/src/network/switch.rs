@@ -0,0 +1,112 @@
+use crate::trgsw::TrgswDftArray;
+use crate::trlwe::Trlwe;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SwitchNetwork {
+    pub(crate) width: usize,
+    pub(crate) switches: Vec<(usize, usize)>,
+}
+
+impl SwitchNetwork {
+    pub fn from_switches(width: usize, switches: Vec<(usize, usize)>) -> Self {
+        for &(a, b) in switches.iter() {
+            assert!(a < width && b < width && a != b);
+        }
+        Self { width, switches }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn num_switches(&self) -> usize {
+        self.switches.len()
+    }
+
+    pub fn switches(&self) -> &[(usize, usize)] {
+        &self.switches
+    }
+
+    pub fn apply(&self, items: &mut [Trlwe], controls: &TrgswDftArray) {
+        assert_eq!(items.len(), self.width);
+        assert_eq!(controls.len(), self.switches.len());
+        for (&(a, b), bit) in self.switches.iter().zip(controls.iter()) {
+            let (in0, in1) = pair_mut(items, a, b);
+            bit.cswap(in0, in1);
+        }
+    }
+
+    pub fn apply_plain<T>(&self, items: &mut [T], controls: &[bool]) {
+        assert_eq!(items.len(), self.width);
+        assert_eq!(controls.len(), self.switches.len());
+        for (&(a, b), &bit) in self.switches.iter().zip(controls.iter()) {
+            if bit {
+                items.swap(a, b);
+            }
+        }
+    }
+}
+
+fn pair_mut<T>(items: &mut [T], a: usize, b: usize) -> (&mut T, &mut T) {
+    if a < b {
+        let (lower, upper) = items.split_at_mut(b);
+        (&mut lower[a], &mut upper[0])
+    } else {
+        let (lower, upper) = items.split_at_mut(a);
+        (&mut upper[0], &mut lower[b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{RawTorus, Torus};
+    use crate::poly::TorusPolynomial;
+    use crate::tlwe::{Tlwe, TlweKey};
+    use crate::trgsw::TrgswKey;
+    use crate::trlwe::{TrlweArray, TrlweKey};
+
+    #[test]
+    fn apply() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 10;
+        let precision = 4;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let width = 8;
+        let perm = [5, 2, 7, 0, 3, 6, 1, 4];
+        let keys = [9, 3, 12, 3, 0, 15, 7, 1];
+        let networks = [
+            (
+                SwitchNetwork::benes(width),
+                SwitchNetwork::benes_controls(&perm),
+            ),
+            (
+                SwitchNetwork::bitonic(width),
+                SwitchNetwork::bitonic(width).sort_controls(&keys),
+            ),
+        ];
+        for (network, controls) in networks {
+            let mut items = TrlweArray::from_fn(width, &trlwe_key, |i| {
+                let elem = Torus::from_unsigned(keys[i], precision);
+                TorusPolynomial::from_elem(upper_n, elem)
+            });
+            let enc_controls = TrgswDftArray::from_fn(
+                network.num_switches(),
+                &trgsw_key,
+                |i| (Torus::from_raw(controls[i] as RawTorus), 0),
+            );
+            network.apply(items.as_slice_mut(), &enc_controls);
+            let mut expected = keys;
+            network.apply_plain(&mut expected, &controls);
+            for (item, &key) in items.iter().zip(expected.iter()) {
+                let tlwe = Tlwe::from_trlwe(item, 0);
+                assert_eq!(tlwe.phase(&tlwe_key).into_unsigned(precision), key);
+            }
+        }
+    }
+}
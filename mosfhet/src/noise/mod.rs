@@ -0,0 +1,3 @@
+mod variance;
+
+pub use variance::*;
@@ -0,0 +1,370 @@
+use crate::params::ParamSet;
+
+// Average-case noise variances, as fractions of the torus squared. Keys are
+// binary (each coefficient has second moment 1/2), gadget decompositions are
+// balanced (each digit is uniform with variance base^2 / 12) and rounding
+// errors are uniform over half of the last decomposition step. Errors from
+// the floating-point FFT are not modeled, so measured variances can be a few
+// tens of percent higher for large N.
+
+pub fn fresh(params: &ParamSet) -> f64 {
+    params.sigma() * params.sigma()
+}
+
+pub fn add(lhs: f64, rhs: f64) -> f64 {
+    lhs + rhs
+}
+
+pub fn sum(count: usize, variance: f64) -> f64 {
+    count as f64 * variance
+}
+
+pub fn scale(variance: f64, scale: i64) -> f64 {
+    (scale as f64) * (scale as f64) * variance
+}
+
+pub fn external_product(params: &ParamSet, trgsw: f64, trlwe: f64) -> f64 {
+    trlwe + gadget(params, trgsw, 1.0)
+}
+
+pub fn cmux(params: &ParamSet, trgsw: f64, lhs: f64, rhs: f64) -> f64 {
+    lhs.max(rhs) + gadget(params, trgsw, 1.0)
+}
+
+pub fn cmux_tree(
+    params: &ParamSet,
+    depth: usize,
+    trgsw: f64,
+    leaf: f64,
+) -> f64 {
+    leaf + depth as f64 * gadget(params, trgsw, 1.0)
+}
+
+pub fn cdemux_tree(
+    params: &ParamSet,
+    depth: usize,
+    trgsw: f64,
+    root: f64,
+) -> f64 {
+    root + depth as f64 * gadget(params, trgsw, 1.0)
+}
+
+// Each step is a CMUX selected by a key bit, so the rounding error of the
+// decomposition only shows up for half of them
+pub fn blind_rotation(params: &ParamSet, lower_n: u32, bootstrap: f64) -> f64 {
+    lower_n as f64 * gadget(params, bootstrap, 0.5)
+}
+
+// Noise of a functional bootstrap output, before any key switching, when the
+// test vector is noiseless
+pub fn bootstrap(params: &ParamSet, lower_n: u32, bootstrap: f64) -> f64 {
+    blind_rotation(params, lower_n, bootstrap)
+}
+
+// Noise added to the input phase when it is rounded to Z_2N before the blind
+// rotation, which must be accounted for when decoding the test vector
+pub fn mod_switch(params: &ParamSet, lower_n: u32) -> f64 {
+    let upper_n = params.upper_n() as f64;
+    (1.0 + lower_n as f64 / 2.0) / (48.0 * upper_n * upper_n)
+}
+
+pub fn key_switch(
+    in_lower_n: u32,
+    t: u32,
+    base_bit: u32,
+    ks_key: f64,
+    input: f64,
+) -> f64 {
+    let n = in_lower_n as f64;
+    // Digits are unsigned and a key sample is only added for nonzero ones
+    let nonzero = 1.0 - (-(base_bit as f64)).exp2();
+    let digits = n * t as f64 * nonzero * ks_key;
+    input + digits + n / 2.0 * rounding(t * base_bit)
+}
+
+// Noise of the first size coefficients when packing size samples into one
+// TRLWE, as each coefficient accumulates the key noise of every sample
+pub fn packing_key_switch(
+    params: &ParamSet,
+    in_lower_n: u32,
+    size: usize,
+    ks_key: f64,
+    input: f64,
+) -> f64 {
+    let n = in_lower_n as f64;
+    let (t, base_bit) = (params.t(), params.base_bit());
+    let digits = n * t as f64 * size as f64 * digit(base_bit) * ks_key;
+    input + digits + n / 2.0 * rounding(t * base_bit)
+}
+
+// Probability that a value encoded with Torus::from_unsigned(_, log_scale)
+// decodes incorrectly, assuming Gaussian noise with the given variance
+pub fn failure_probability(variance: f64, log_scale: usize) -> f64 {
+    let bound = (-(log_scale as f64) - 1.0).exp2();
+    erfc(bound / (2.0 * variance).sqrt())
+}
+
+// The rounding error is scaled by the TRGSW message, whose second moment is
+// given in message
+fn gadget(params: &ParamSet, trgsw: f64, message: f64) -> f64 {
+    let k = params.k() as f64;
+    let upper_n = params.upper_n() as f64;
+    let l = params.l() as f64;
+    let digits = (k + 1.0) * l * upper_n * digit(params.bg_bit()) * trgsw;
+    let bits = params.l() * params.bg_bit();
+    digits + message * (1.0 + k * upper_n / 2.0) * rounding(bits)
+}
+
+fn digit(base_bit: u32) -> f64 {
+    (2.0 * base_bit as f64).exp2() / 12.0
+}
+
+fn rounding(bits: u32) -> f64 {
+    (-2.0 * bits as f64).exp2() / 12.0
+}
+
+// Complementary error function with relative error below 1.2e-7, from
+// Numerical Recipes
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let coeffs = [
+        -1.26551223,
+        1.00002368,
+        0.37409196,
+        0.09678418,
+        -0.18628806,
+        0.27886807,
+        -1.13520398,
+        1.48851587,
+        -0.82215223,
+        0.17087277,
+    ];
+    let poly = coeffs.iter().rev().fold(0.0, |acc, c| c + t * acc);
+    let y = t * (poly - z * z).exp();
+    if x >= 0.0 {
+        y
+    } else {
+        2.0 - y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bootstrap::BootstrapKey;
+    use crate::common::{RawTorus, Torus};
+    use crate::poly::TorusPolynomial;
+    use crate::tlwe::{Tlwe, TlweArray, TlweKSKey, TlweKey};
+    use crate::trgsw::{Trgsw, TrgswDft};
+    use crate::trlwe::{Trlwe, TrlweDft, TrlweKey};
+
+    fn squared(phase: Torus, expected: Torus) -> f64 {
+        phase.distance(expected).into_double().powi(2)
+    }
+
+    fn measure(trlwe: &Trlwe, key: &TrlweKey) -> f64 {
+        let phase = trlwe.phase(key);
+        let sum = phase.iter().map(|v| squared(*v, Torus::MIN)).sum::<f64>();
+        sum / trlwe.upper_n() as f64
+    }
+
+    fn assert_close(measured: f64, predicted: f64) {
+        let ratio = measured / predicted;
+        assert!(
+            (0.5..2.0).contains(&ratio),
+            "measured {measured:e}, predicted {predicted:e}"
+        );
+    }
+
+    #[test]
+    fn external_product() {
+        let upper_n = 1024;
+        for sigma in [4.884981308350689e-16, 1e-12] {
+            let builder = ParamSet::builder().upper_n(upper_n).sigma(sigma);
+            let params = builder.build().unwrap();
+            let trlwe_key = params.trlwe_key();
+            let trgsw_key = params.trgsw_key(&trlwe_key);
+            let trials = 8;
+            let measured = (0..trials)
+                .map(|_| {
+                    let zero = TorusPolynomial::zeroed(upper_n);
+                    let trlwe = Trlwe::new(zero, &trlwe_key);
+                    let one = Torus::from_raw(1);
+                    let trgsw = Trgsw::new(one, 0, &trgsw_key);
+                    let trgsw = TrgswDft::from_trgsw(&trgsw);
+                    let output = TrlweDft::mul_trlwe_dft(&trlwe, &trgsw);
+                    measure(&Trlwe::from_dft(&output), &trlwe_key)
+                })
+                .sum::<f64>()
+                / trials as f64;
+            let predicted = super::external_product(
+                &params,
+                fresh(&params),
+                fresh(&params),
+            );
+            assert_close(measured, predicted);
+        }
+    }
+
+    #[test]
+    fn key_switch() {
+        let in_lower_n = 1024;
+        let in_sigma = 9.094947017729282e-13;
+        let out_sigma = 9.5367431640625e-07;
+        let trlwe_key = TrlweKey::new(in_lower_n, 1, in_sigma);
+        let in_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let out_key = TlweKey::new_binary(256, out_sigma);
+        // Dominated by the rounding error and by the key noise, respectively
+        for (t, base_bit) in [(4, 3), (10, 3)] {
+            let ks_key = TlweKSKey::new(&in_key, &out_key, t, base_bit);
+            let trials = 256;
+            let measured = (0..trials)
+                .map(|_| {
+                    let output = Tlwe::zero(&in_key).key_switch(&ks_key);
+                    squared(output.phase(&out_key), Torus::MIN)
+                })
+                .sum::<f64>()
+                / trials as f64;
+            let predicted = super::key_switch(
+                in_lower_n,
+                t,
+                base_bit,
+                out_sigma * out_sigma,
+                in_sigma * in_sigma,
+            );
+            assert_close(measured, predicted);
+        }
+    }
+
+    #[test]
+    fn packing_key_switch() {
+        let upper_n = 1024;
+        let lower_n = 128;
+        let in_sigma = 9.094947017729282e-13;
+        let builder = ParamSet::builder().upper_n(upper_n).sigma(1e-9);
+        let params = builder.t(3).base_bit(8).build().unwrap();
+        let trlwe_key = params.trlwe_key();
+        let tlwe_key = TlweKey::new_binary(lower_n, in_sigma);
+        let pks_key = params.pks_key(&tlwe_key, &trlwe_key);
+        for size in [16, 256] {
+            let trials = upper_n as usize / size;
+            let measured = (0..trials)
+                .map(|_| {
+                    let inputs = TlweArray::zeroed(size, &tlwe_key);
+                    let output =
+                        Trlwe::from_tlwe_array(&inputs, 0, size, &pks_key);
+                    let phase = output.phase(&trlwe_key);
+                    let phase = phase.iter().take(size);
+                    phase.map(|v| squared(*v, Torus::MIN)).sum::<f64>()
+                })
+                .sum::<f64>()
+                / upper_n as f64;
+            let predicted = super::packing_key_switch(
+                &params,
+                lower_n,
+                size,
+                fresh(&params),
+                in_sigma * in_sigma,
+            );
+            assert_close(measured, predicted);
+        }
+    }
+
+    #[test]
+    fn mod_switch() {
+        let params = ParamSet::builder().upper_n(1024).build().unwrap();
+        let lower_n = 512;
+        let key = TlweKey::new_binary(lower_n, 9.5367431640625e-07);
+        let shift = RawTorus::BITS - (2 * params.upper_n()).ilog2();
+        let round =
+            |x: RawTorus| (x.wrapping_add(1 << (shift - 1)) >> shift) << shift;
+        let trials = 1024;
+        let measured = (0..trials)
+            .map(|_| {
+                let mut sample = Tlwe::zero(&key);
+                let phase = sample.phase(&key);
+                unsafe {
+                    let ptr = sample.as_ptr_mut() as mosfhet_sys::TLWE;
+                    let a = std::slice::from_raw_parts_mut(
+                        (*ptr).a,
+                        lower_n as usize,
+                    );
+                    a.iter_mut().for_each(|x| *x = round(*x));
+                    (*ptr).b = round((*ptr).b);
+                }
+                squared(sample.phase(&key), phase)
+            })
+            .sum::<f64>()
+            / trials as f64;
+        let predicted = super::mod_switch(&params, lower_n);
+        assert_close(measured, predicted);
+    }
+
+    #[test]
+    fn bootstrap() {
+        let upper_n = 1024;
+        let lower_n = 64;
+        let tlwe_key = TlweKey::new_binary(lower_n, 9.5367431640625e-07);
+        let value = Torus::from_unsigned(1, 3);
+        let neg_value = Torus::from_raw(value.into_raw().wrapping_neg());
+        let poly = TorusPolynomial::from_elem(upper_n, value);
+        let test_vector = Trlwe::new_noiseless(poly, 1, upper_n);
+        // Dominated by the rounding error and by the key noise, respectively
+        for sigma in [9.094947017729282e-13, 1e-8] {
+            let builder = ParamSet::builder().upper_n(upper_n).sigma(sigma);
+            let params = builder.l(3).bg_bit(7).build().unwrap();
+            let trlwe_key = params.trlwe_key();
+            let trgsw_key = params.trgsw_key(&trlwe_key);
+            let out_key = TlweKey::from_trlwe_key(&trlwe_key);
+            let bootstrap_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+            let trials = 128;
+            let measured = (0..trials)
+                .map(|i| {
+                    let m = Torus::from_unsigned(i, 7);
+                    let input = Tlwe::new(m, &tlwe_key);
+                    let output = input.functional_bootstrap(
+                        &test_vector,
+                        &bootstrap_key,
+                        2,
+                    );
+                    // The output is either value or its negation, depending
+                    // on the rotation
+                    let phase = output.phase(&out_key);
+                    squared(phase, value).min(squared(phase, neg_value))
+                })
+                .sum::<f64>()
+                / trials as f64;
+            let predicted = super::bootstrap(&params, lower_n, fresh(&params));
+            assert_close(measured, predicted);
+        }
+    }
+
+    #[test]
+    fn failure() {
+        let log_scale = 4;
+        let bound = (-(log_scale as f64) - 1.0).exp2();
+        let p = failure_probability(bound * bound, log_scale);
+        assert!((p - 0.3173105).abs() < 1e-6);
+        let p = failure_probability(bound * bound / 9.0, log_scale);
+        assert!((p - 0.0026998).abs() < 1e-6);
+        assert!(failure_probability(1e-20, log_scale) < 1e-100);
+        assert!((erfc(-1.0) - 1.8427008).abs() < 1e-6);
+    }
+
+    #[test]
+    fn composition() {
+        let params = ParamSet::SMALL;
+        let trgsw = fresh(&params);
+        let leaf = fresh(&params);
+        assert_eq!(cmux_tree(&params, 0, trgsw, leaf), leaf);
+        let step = cmux(&params, trgsw, leaf, leaf);
+        let tree = cmux_tree(&params, 10, trgsw, leaf);
+        assert!((tree - (leaf + 10.0 * (step - leaf))).abs() < 1e-24);
+        assert_eq!(sum(4, leaf), scale(leaf, 2));
+        let rotation = blind_rotation(&params, 630, trgsw);
+        assert_eq!(super::bootstrap(&params, 630, trgsw), rotation);
+        assert!(rotation < 630.0 * (step - leaf));
+        assert!(rotation > 315.0 * (step - leaf));
+    }
+}
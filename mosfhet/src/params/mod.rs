@@ -0,0 +1,4 @@
+pub mod security;
+mod set;
+
+pub use set::*;
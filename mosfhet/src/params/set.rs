@@ -0,0 +1,298 @@
+use crate::common::RawTorus;
+use crate::error::{check, check_sigma};
+use crate::params::security::SecurityEstimate;
+use crate::tlwe::TlweKey;
+use crate::trgsw::TrgswKey;
+use crate::trlwe::{TrlweKey, TrlwePKSKey};
+use crate::{Error, Result};
+
+// Gadget decompositions are computed in double precision, so they cannot use
+// more than 54 bits of the torus
+const MAX_BITS: u32 = if RawTorus::BITS < 54 {
+    RawTorus::BITS
+} else {
+    54
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ParamSet {
+    upper_n: u32,
+    k: u32,
+    sigma: f64,
+    l: u32,
+    bg_bit: u32,
+    t: u32,
+    base_bit: u32,
+}
+
+impl ParamSet {
+    // Settings used by scripts/run-*-tiny.sh and scripts/run-*-small.sh
+    pub const TINY: Self = Self::SMALL;
+    pub const SMALL: Self = Self {
+        upper_n: 2048,
+        k: 1,
+        sigma: 4.884981308350689e-16,
+        l: 1,
+        bg_bit: 23,
+        t: 2,
+        base_bit: 15,
+    };
+
+    // Settings used by scripts/run-*-medium.sh and scripts/run-*-large.sh
+    pub const MEDIUM: Self = Self::LARGE;
+    pub const LARGE: Self = Self {
+        upper_n: 2048,
+        k: 1,
+        sigma: 4.884981308350689e-16,
+        l: 2,
+        bg_bit: 15,
+        t: 3,
+        base_bit: 11,
+    };
+
+    pub fn builder() -> ParamSetBuilder {
+        ParamSetBuilder::from(Self::SMALL)
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "tiny" => Some(Self::TINY),
+            "small" => Some(Self::SMALL),
+            "medium" => Some(Self::MEDIUM),
+            "large" => Some(Self::LARGE),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let bits = |a: u32, b: u32| a as u64 * b as u64;
+        check(self.upper_n.is_power_of_two(), "upper_n", self.upper_n)?;
+        check(self.k > 0, "k", self.k)?;
+        check_sigma(self.sigma)?;
+        check(self.l > 0, "l", self.l)?;
+        check(self.bg_bit > 0, "bg_bit", self.bg_bit)?;
+        let l_bits = bits(self.l, self.bg_bit);
+        check(l_bits <= MAX_BITS as u64, "l * bg_bit", l_bits)?;
+        check(self.t > 0, "t", self.t)?;
+        check(self.base_bit > 0, "base_bit", self.base_bit)?;
+        let t_bits = bits(self.t, self.base_bit);
+        check(t_bits <= MAX_BITS as u64, "t * base_bit", t_bits)
+    }
+
+    pub fn security(&self) -> SecurityEstimate {
+        SecurityEstimate::new(self.lower_n(), self.sigma)
+    }
+
+    pub fn check_security(&self, required: f64) -> Result<()> {
+        let bits = self.security().bits();
+        if bits < required {
+            return Err(Error::InsufficientSecurity { bits, required });
+        }
+        Ok(())
+    }
+
+    pub fn upper_n(&self) -> u32 {
+        self.upper_n
+    }
+
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    pub fn lower_n(&self) -> u32 {
+        self.k * self.upper_n
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+
+    pub fn l(&self) -> u32 {
+        self.l
+    }
+
+    pub fn bg_bit(&self) -> u32 {
+        self.bg_bit
+    }
+
+    pub fn t(&self) -> u32 {
+        self.t
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        self.base_bit
+    }
+
+    pub fn trlwe_key(&self) -> TrlweKey {
+        TrlweKey::new(self.upper_n, self.k, self.sigma)
+    }
+
+    pub fn trgsw_key(&self, trlwe_key: &TrlweKey) -> TrgswKey {
+        assert_eq!(trlwe_key.k(), self.k);
+        assert_eq!(trlwe_key.upper_n(), self.upper_n);
+        TrgswKey::new(trlwe_key, self.l, self.bg_bit)
+    }
+
+    pub fn pks_key(
+        &self,
+        tlwe_key: &TlweKey,
+        trlwe_key: &TrlweKey,
+    ) -> TrlwePKSKey {
+        assert_eq!(trlwe_key.k(), self.k);
+        assert_eq!(trlwe_key.upper_n(), self.upper_n);
+        TrlwePKSKey::new(tlwe_key, trlwe_key, self.t, self.base_bit)
+    }
+
+    pub fn keys(&self) -> KeySet {
+        let trlwe_key = self.trlwe_key();
+        let trgsw_key = self.trgsw_key(&trlwe_key);
+        let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let pks_key = self.pks_key(&tlwe_key, &trlwe_key);
+        KeySet {
+            trlwe_key,
+            trgsw_key,
+            tlwe_key,
+            pks_key,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ParamSetBuilder {
+    params: ParamSet,
+    min_security: Option<f64>,
+}
+
+impl ParamSetBuilder {
+    pub fn upper_n(mut self, upper_n: u32) -> Self {
+        self.params.upper_n = upper_n;
+        self
+    }
+
+    pub fn k(mut self, k: u32) -> Self {
+        self.params.k = k;
+        self
+    }
+
+    pub fn sigma(mut self, sigma: f64) -> Self {
+        self.params.sigma = sigma;
+        self
+    }
+
+    pub fn l(mut self, l: u32) -> Self {
+        self.params.l = l;
+        self
+    }
+
+    pub fn bg_bit(mut self, bg_bit: u32) -> Self {
+        self.params.bg_bit = bg_bit;
+        self
+    }
+
+    pub fn t(mut self, t: u32) -> Self {
+        self.params.t = t;
+        self
+    }
+
+    pub fn base_bit(mut self, base_bit: u32) -> Self {
+        self.params.base_bit = base_bit;
+        self
+    }
+
+    pub fn min_security(mut self, bits: f64) -> Self {
+        self.min_security = Some(bits);
+        self
+    }
+
+    pub fn build(self) -> Result<ParamSet> {
+        self.params.validate()?;
+        if let Some(bits) = self.min_security {
+            self.params.check_security(bits)?;
+        }
+        Ok(self.params)
+    }
+}
+
+impl From<ParamSet> for ParamSetBuilder {
+    fn from(params: ParamSet) -> Self {
+        Self {
+            params,
+            min_security: None,
+        }
+    }
+}
+
+pub struct KeySet {
+    pub trlwe_key: TrlweKey,
+    pub trgsw_key: TrgswKey,
+    pub tlwe_key: TlweKey,
+    pub pks_key: TrlwePKSKey,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets() {
+        for name in ["tiny", "small", "medium", "large"] {
+            let params = ParamSet::preset(name).unwrap();
+            assert!(params.validate().is_ok());
+            let built = ParamSetBuilder::from(params).build().unwrap();
+            assert_eq!(built, params);
+        }
+        assert_eq!(ParamSet::preset("small"), Some(ParamSet::SMALL));
+        assert_eq!(ParamSet::preset("huge"), None);
+        assert_eq!(ParamSet::builder().build().unwrap(), ParamSet::SMALL);
+    }
+
+    #[test]
+    fn security() {
+        for params in [ParamSet::SMALL, ParamSet::LARGE] {
+            assert!(params.security().bits() >= 128.0);
+            assert!(params.check_security(128.0).is_ok());
+        }
+        let builder = ParamSet::builder().min_security(128.0);
+        assert!(builder.build().is_ok());
+        let result = builder.upper_n(1024).build();
+        assert!(matches!(result, Err(Error::InsufficientSecurity { .. })));
+        assert!(builder.upper_n(1024).sigma(5.5e-8).build().is_ok());
+    }
+
+    #[test]
+    fn validation() {
+        let invalid = |builder: ParamSetBuilder, param| {
+            matches!(
+                builder.build(),
+                Err(Error::InvalidParameter { name, .. }) if name == param
+            )
+        };
+        let builder = ParamSet::builder();
+        assert!(invalid(builder.upper_n(1000), "upper_n"));
+        assert!(invalid(builder.k(0), "k"));
+        assert!(invalid(builder.l(0), "l"));
+        assert!(invalid(builder.bg_bit(0), "bg_bit"));
+        assert!(invalid(builder.t(0), "t"));
+        assert!(invalid(builder.base_bit(0), "base_bit"));
+        assert!(invalid(builder.l(3).bg_bit(19), "l * bg_bit"));
+        assert!(invalid(builder.t(4).base_bit(14), "t * base_bit"));
+        for sigma in [0.0, 1.0, -1e-9, f64::NAN] {
+            let result = builder.sigma(sigma).build();
+            assert!(matches!(result, Err(Error::InvalidSigma { .. })));
+        }
+        assert!(builder.l(2).bg_bit(MAX_BITS / 2).build().is_ok());
+    }
+
+    #[test]
+    fn keys() {
+        let params = ParamSet::builder().upper_n(256).t(2).base_bit(8);
+        let params = params.build().unwrap();
+        let keys = params.keys();
+        assert_eq!(keys.trlwe_key.upper_n(), 256);
+        assert_eq!(keys.trlwe_key.k(), 1);
+        assert_eq!(keys.tlwe_key.lower_n(), params.lower_n());
+        assert_eq!(keys.trgsw_key.l(), params.l());
+        assert_eq!(keys.trgsw_key.bg_bit(), params.bg_bit());
+        assert_eq!(keys.pks_key.out_upper_n(), 256);
+    }
+}
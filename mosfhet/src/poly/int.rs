@@ -0,0 +1,142 @@
+use std::ops::{Index, IndexMut};
+
+use crate::common::macros::*;
+use crate::common::Integer;
+
+#[repr(transparent)]
+pub struct IntPolynomial {
+    ptr: mosfhet_sys::IntPolynomial,
+}
+
+impl IntPolynomial {
+    pub(crate) unsafe fn new_uninit(upper_n: u32) -> Self {
+        Self {
+            ptr: mosfhet_sys::polynomial_new_torus_polynomial(upper_n as i32),
+        }
+    }
+
+    pub fn upper_n(&self) -> u32 {
+        unsafe { (*self.ptr).N as u32 }
+    }
+
+    pub fn as_slice(&self) -> &[Integer] {
+        unsafe {
+            let len = (*self.ptr).N as usize;
+            let ptr = (*self.ptr).coeffs as *const Integer;
+            std::slice::from_raw_parts(ptr, len)
+        }
+    }
+
+    pub fn as_slice_mut(&mut self) -> &mut [Integer] {
+        unsafe {
+            let len = (*self.ptr).N as usize;
+            let ptr = (*self.ptr).coeffs as *mut Integer;
+            std::slice::from_raw_parts_mut(ptr, len)
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Integer> {
+        self.as_slice().iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Integer> {
+        self.as_slice_mut().iter_mut()
+    }
+
+    pub fn from_fn<F>(upper_n: u32, f: F) -> Self
+    where
+        F: Fn(usize) -> Integer,
+    {
+        let mut out = unsafe { Self::new_uninit(upper_n) };
+        for i in 0..(upper_n as usize) {
+            out[i] = f(i);
+        }
+        out
+    }
+
+    pub fn from_elem(upper_n: u32, elem: Integer) -> Self {
+        Self::from_fn(upper_n, |_| elem)
+    }
+
+    pub fn zeroed(upper_n: u32) -> Self {
+        Self::from_elem(upper_n, 0)
+    }
+
+    pub fn monomial(upper_n: u32, coeff: Integer, e: u32) -> Self {
+        let mut out = Self::zeroed(upper_n);
+        let e = e as usize % (2 * upper_n as usize);
+        if e < upper_n as usize {
+            out[e] = coeff;
+        } else {
+            out[e - upper_n as usize] = coeff.wrapping_neg();
+        }
+        out
+    }
+}
+
+impl_drop!(IntPolynomial => free_polynomial);
+impl_ptrs!(IntPolynomial);
+
+unsafe impl Send for IntPolynomial {}
+unsafe impl Sync for IntPolynomial {}
+
+impl Clone for IntPolynomial {
+    fn clone(&self) -> Self {
+        unsafe {
+            let mut out = Self::new_uninit(self.upper_n());
+            out.clone_from(self);
+            out
+        }
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        unsafe {
+            mosfhet_sys::polynomial_copy_torus_polynomial(self.ptr, source.ptr)
+        }
+    }
+}
+
+impl Index<usize> for IntPolynomial {
+    type Output = Integer;
+    fn index(&self, index: usize) -> &Self::Output {
+        self.as_slice().index(index)
+    }
+}
+
+impl IndexMut<usize> for IntPolynomial {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.as_slice_mut().index_mut(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_uninit_upper_n() {
+        let upper_n = 16;
+        let poly = unsafe { IntPolynomial::new_uninit(upper_n) };
+        assert_eq!(poly.upper_n(), upper_n);
+    }
+
+    #[test]
+    fn zeroed_clone_iter_mut_monomial() {
+        let upper_n = 16;
+        let mut poly = IntPolynomial::zeroed(upper_n);
+        let clone = poly.clone();
+        assert_eq!(poly.as_slice(), clone.as_slice());
+        poly.iter_mut()
+            .enumerate()
+            .for_each(|(i, p)| *p = i as u64 + 1);
+        assert_eq!(poly[upper_n as usize - 1], upper_n as u64);
+        poly.iter()
+            .zip(clone.iter())
+            .for_each(|(p, e)| assert_ne!(p, e));
+        let poly = IntPolynomial::monomial(upper_n, 3, 2);
+        assert_eq!(poly[2], 3);
+        let poly = IntPolynomial::monomial(upper_n, 3, upper_n + 2);
+        assert_eq!(poly[2], 3u64.wrapping_neg());
+        assert_eq!(poly.iter().filter(|&&c| c != 0).count(), 1);
+    }
+}
@@ -1,7 +1,9 @@
 mod binary;
 mod dft;
+mod int;
 mod torus;
 
 pub use binary::*;
 pub use dft::*;
+pub use int::*;
 pub use torus::*;
@@ -72,6 +72,16 @@ impl TorusPolynomial {
         output
     }
 
+    pub fn from_ks21_lut_fn<F>(upper_n: u32, torus_base: u32, f: F) -> Self
+    where
+        F: Fn(usize) -> Torus,
+    {
+        assert!(torus_base > 0);
+        assert_eq!((2 * upper_n) % torus_base, 0);
+        let slot_size = (2 * upper_n / torus_base) as usize;
+        Self::from_fn(2 * upper_n, |i| f(i / slot_size))
+    }
+
     pub fn from_elem(upper_n: u32, elem: Torus) -> Self {
         Self::from_fn(upper_n, |_| elem)
     }
@@ -447,4 +457,42 @@ mod tests {
         let poly_b = poly_a.mul_by_xai_pred(1);
         assert_eq!(poly_b.as_slice(), expected.as_slice());
     }
+
+    #[test]
+    fn mul_by_xai_zero() {
+        use crate::common::RawTorus;
+        let upper_n = 8;
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            Torus::from_raw(i as RawTorus + 1)
+        });
+        let mut out = TorusPolynomial::zeroed(upper_n);
+        out.mul_by_xai_from(&poly, 0);
+        assert_eq!(out.as_slice(), poly.as_slice());
+        out.mul_by_xai_from(&poly, 2 * upper_n);
+        assert_eq!(out.as_slice(), poly.as_slice());
+    }
+
+    #[test]
+    fn mul_mixed_sizes() {
+        use crate::common::RawTorus;
+        // The DFT product is only exact up to a small rounding error
+        let close = |lhs: &TorusPolynomial, rhs: &TorusPolynomial| {
+            lhs.iter()
+                .zip(rhs.iter())
+                .all(|(a, b)| a.distance(*b).into_raw() <= 2)
+        };
+        for upper_n in [256, 512, 2048, 256] {
+            let lhs = TorusPolynomial::from_fn(upper_n, |i| {
+                Torus::from_raw(i as RawTorus % 7)
+            });
+            let rhs = TorusPolynomial::from_fn(upper_n, |i| {
+                Torus::from_raw(i as RawTorus % 5)
+            });
+            let expected = lhs.naive_mul(&rhs);
+            assert!(close(&lhs.mul(&rhs), &expected));
+            let mut out = TorusPolynomial::zeroed(upper_n);
+            out.mul_add_assign(&lhs, &rhs);
+            assert!(close(&out, &expected));
+        }
+    }
 }
@@ -1,5 +1,7 @@
 use crate::common::macros::*;
+use crate::error::{check, check_sigma};
 use crate::trlwe::TrlweKey;
+use crate::Result;
 
 #[repr(transparent)]
 pub struct TlweKey {
@@ -13,17 +15,33 @@ impl TlweKey {
     }
 
     pub fn new_binary(lower_n: u32, sigma: f64) -> Self {
+        Self::try_new_binary(lower_n, sigma).unwrap()
+    }
+
+    pub fn try_new_binary(lower_n: u32, sigma: f64) -> Result<Self> {
+        check(lower_n > 0, "lower_n", lower_n)?;
+        check_sigma(sigma)?;
         let ptr =
             unsafe { mosfhet_sys::tlwe_new_binary_key(lower_n as i32, sigma) };
-        Self { ptr }
+        Ok(Self { ptr })
     }
 
     pub fn new_bounded(lower_n: u32, bound: u64, sigma: f64) -> Self {
-        assert!(bound.is_power_of_two());
+        Self::try_new_bounded(lower_n, bound, sigma).unwrap()
+    }
+
+    pub fn try_new_bounded(
+        lower_n: u32,
+        bound: u64,
+        sigma: f64,
+    ) -> Result<Self> {
+        check(lower_n > 0, "lower_n", lower_n)?;
+        check(bound.is_power_of_two(), "bound", bound)?;
+        check_sigma(sigma)?;
         let ptr = unsafe {
             mosfhet_sys::tlwe_new_bounded_key(lower_n as i32, bound, sigma)
         };
-        Self { ptr }
+        Ok(Self { ptr })
     }
 
     pub fn lower_n(&self) -> u32 {
@@ -48,8 +66,9 @@ impl TlweKey {
     }
 }
 
-impl_load!(TlweKey => tlwe_load_new_key);
-impl_save!(TlweKey => tlwe_save_key);
+impl_load!(TlweKey => tlwe_load_new_key; lower_n);
+impl_save!(TlweKey => tlwe_save_key(lower_n));
+impl_serde!(TlweKey);
 impl_drop!(TlweKey => free_tlwe_key);
 impl_ptrs!(TlweKey);
 
@@ -97,8 +116,9 @@ impl TlweKSKey {
     }
 }
 
-impl_load!(TlweKSKey => tlwe_load_new_KS_key);
-impl_save!(TlweKSKey => tlwe_save_KS_key);
+impl_load!(TlweKSKey => tlwe_load_new_KS_key; in_lower_n, out_lower_n, t, base_bit);
+impl_save!(TlweKSKey => tlwe_save_KS_key(in_lower_n, out_lower_n, t, base_bit));
+impl_serde!(TlweKSKey);
 impl_drop!(TlweKSKey => free_tlwe_ks_key);
 impl_ptrs!(TlweKSKey);
 
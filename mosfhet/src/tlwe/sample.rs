@@ -1,9 +1,13 @@
+use crate::bootstrap::{BootstrapGaKey, BootstrapKey, MultiValueBootstrap};
 use crate::common::macros::*;
-use crate::common::Torus;
+use crate::common::{format, Integer, RawTorus, Torus};
+use crate::error::{check_eq, check_scale};
+use crate::poly::TorusPolynomial;
 use crate::tlwe::{TlweKSKey, TlweKey};
-use crate::trlwe::Trlwe;
+use crate::trlwe::{RelinearizationKey, Trlwe, TrlweArray, TrlwePacking1KSKey};
+use crate::Result;
 
-use std::ops::{Index, IndexMut};
+use std::ops::{Index, IndexMut, Range};
 
 #[repr(transparent)]
 pub struct Tlwe {
@@ -16,6 +20,10 @@ impl Tlwe {
         Self { ptr }
     }
 
+    fn sample_bytes(lower_n: u32) -> Option<usize> {
+        format::bytes::<RawTorus>(&[lower_n as usize + 1])
+    }
+
     pub fn new(m: Torus, key: &TlweKey) -> Self {
         let ptr = unsafe {
             mosfhet_sys::tlwe_new_sample(m.0, key.as_ptr() as *mut _)
@@ -66,12 +74,93 @@ impl Tlwe {
         }
     }
 
+    pub fn from_trlwe_scaling(sample: &Trlwe, scale: u32) -> Tlwe {
+        Self::try_from_trlwe_scaling(sample, scale).unwrap()
+    }
+
+    pub fn try_from_trlwe_scaling(sample: &Trlwe, scale: u32) -> Result<Tlwe> {
+        let k = sample.k();
+        let upper_n = sample.upper_n();
+        let mut output = unsafe { Tlwe::new_uninit(k * upper_n) };
+        output.try_set_from_trlwe_scaling(sample, scale)?;
+        Ok(output)
+    }
+
+    pub fn set_from_trlwe_scaling(&mut self, sample: &Trlwe, scale: u32) {
+        self.try_set_from_trlwe_scaling(sample, scale).unwrap()
+    }
+
+    pub fn try_set_from_trlwe_scaling(
+        &mut self,
+        sample: &Trlwe,
+        scale: u32,
+    ) -> Result<()> {
+        check_eq("lower_n", sample.k() * sample.upper_n(), self.lower_n())?;
+        check_scale(scale, sample.upper_n())?;
+        unsafe {
+            mosfhet_sys::trlwe_mv_extract_tlwe_scaling(
+                self.ptr,
+                sample.as_ptr() as *mut _,
+                scale as i32,
+            )
+        }
+        Ok(())
+    }
+
+    pub fn add_assign_trlwe_scaling(&mut self, sample: &Trlwe, scale: u32) {
+        self.try_add_assign_trlwe_scaling(sample, scale).unwrap()
+    }
+
+    pub fn try_add_assign_trlwe_scaling(
+        &mut self,
+        sample: &Trlwe,
+        scale: u32,
+    ) -> Result<()> {
+        check_eq("lower_n", sample.k() * sample.upper_n(), self.lower_n())?;
+        check_scale(scale, sample.upper_n())?;
+        unsafe {
+            mosfhet_sys::trlwe_mv_extract_tlwe_scaling_addto(
+                self.ptr,
+                sample.as_ptr() as *mut _,
+                scale as i32,
+            )
+        }
+        Ok(())
+    }
+
+    pub fn sub_assign_trlwe_scaling(&mut self, sample: &Trlwe, scale: u32) {
+        self.try_sub_assign_trlwe_scaling(sample, scale).unwrap()
+    }
+
+    pub fn try_sub_assign_trlwe_scaling(
+        &mut self,
+        sample: &Trlwe,
+        scale: u32,
+    ) -> Result<()> {
+        check_eq("lower_n", sample.k() * sample.upper_n(), self.lower_n())?;
+        check_scale(scale, sample.upper_n())?;
+        unsafe {
+            mosfhet_sys::trlwe_mv_extract_tlwe_scaling_subto(
+                self.ptr,
+                sample.as_ptr() as *mut _,
+                scale as i32,
+            )
+        }
+        Ok(())
+    }
+
     pub fn lower_n(&self) -> u32 {
         unsafe { (*self.ptr).n as u32 }
     }
 
     pub fn add_assign(&mut self, rhs: &Self) {
+        self.try_add_assign(rhs).unwrap()
+    }
+
+    pub fn try_add_assign(&mut self, rhs: &Self) -> Result<()> {
+        check_eq("lower_n", self.lower_n(), rhs.lower_n())?;
         unsafe { mosfhet_sys::tlwe_addto(self.ptr, rhs.ptr) }
+        Ok(())
     }
 
     pub fn add_from(&mut self, lhs: &Self, rhs: &Self) {
@@ -79,15 +168,25 @@ impl Tlwe {
     }
 
     pub fn add(&self, other: &Self) -> Self {
+        self.try_add(other).unwrap()
+    }
+
+    pub fn try_add(&self, other: &Self) -> Result<Self> {
         let lower_n = self.lower_n();
-        assert_eq!(lower_n, other.lower_n());
+        check_eq("lower_n", lower_n, other.lower_n())?;
         let mut output = unsafe { Self::new_uninit(lower_n) };
         output.add_from(self, other);
-        output
+        Ok(output)
     }
 
     pub fn sub_assign(&mut self, other: &Self) {
+        self.try_sub_assign(other).unwrap()
+    }
+
+    pub fn try_sub_assign(&mut self, other: &Self) -> Result<()> {
+        check_eq("lower_n", self.lower_n(), other.lower_n())?;
         unsafe { mosfhet_sys::tlwe_subto(self.ptr, other.ptr) }
+        Ok(())
     }
 
     pub fn sub_from(&mut self, lhs: &Self, rhs: &Self) {
@@ -95,11 +194,15 @@ impl Tlwe {
     }
 
     pub fn sub(&self, other: &Self) -> Self {
+        self.try_sub(other).unwrap()
+    }
+
+    pub fn try_sub(&self, other: &Self) -> Result<Self> {
         let lower_n = self.lower_n();
-        assert_eq!(lower_n, other.lower_n());
+        check_eq("lower_n", lower_n, other.lower_n())?;
         let mut output = unsafe { Self::new_uninit(lower_n) };
         output.sub_from(self, other);
-        output
+        Ok(output)
     }
 
     pub fn neg_from(&mut self, input: &Self) {
@@ -113,6 +216,37 @@ impl Tlwe {
         output
     }
 
+    pub fn scale_from(&mut self, input: &Self, k: Integer) {
+        self.try_scale_from(input, k).unwrap()
+    }
+
+    pub fn try_scale_from(&mut self, input: &Self, k: Integer) -> Result<()> {
+        check_eq("lower_n", self.lower_n(), input.lower_n())?;
+        unsafe { mosfhet_sys::tlwe_scale(self.ptr, input.ptr, k) }
+        Ok(())
+    }
+
+    pub fn scale(&self, k: Integer) -> Self {
+        let lower_n = self.lower_n();
+        let mut output = unsafe { Self::new_uninit(lower_n) };
+        output.scale_from(self, k);
+        output
+    }
+
+    pub fn scale_add_assign(&mut self, input: &Self, k: Integer) {
+        self.try_scale_add_assign(input, k).unwrap()
+    }
+
+    pub fn try_scale_add_assign(
+        &mut self,
+        input: &Self,
+        k: Integer,
+    ) -> Result<()> {
+        check_eq("lower_n", self.lower_n(), input.lower_n())?;
+        unsafe { mosfhet_sys::tlwe_scale_addto(self.ptr, input.ptr, k) }
+        Ok(())
+    }
+
     pub fn phase(&self, key: &TlweKey) -> Torus {
         unsafe {
             Torus::from_raw(mosfhet_sys::tlwe_phase(
@@ -123,6 +257,16 @@ impl Tlwe {
     }
 
     pub fn key_switch_from(&mut self, input: &Self, key: &TlweKSKey) {
+        self.try_key_switch_from(input, key).unwrap()
+    }
+
+    pub fn try_key_switch_from(
+        &mut self,
+        input: &Self,
+        key: &TlweKSKey,
+    ) -> Result<()> {
+        check_eq("lower_n", key.in_lower_n(), input.lower_n())?;
+        check_eq("lower_n", key.out_lower_n(), self.lower_n())?;
         unsafe {
             mosfhet_sys::tlwe_keyswitch(
                 self.ptr,
@@ -130,18 +274,318 @@ impl Tlwe {
                 key.as_ptr() as *mut _,
             )
         }
+        Ok(())
     }
 
     pub fn key_switch(&self, key: &TlweKSKey) -> Self {
+        self.try_key_switch(key).unwrap()
+    }
+
+    pub fn try_key_switch(&self, key: &TlweKSKey) -> Result<Self> {
         let lower_n = key.out_lower_n();
         let mut output = unsafe { Self::new_uninit(lower_n) };
-        output.key_switch_from(self, key);
+        output.try_key_switch_from(self, key)?;
+        Ok(output)
+    }
+
+    pub fn functional_bootstrap_from(
+        &mut self,
+        input: &Self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) {
+        self.try_functional_bootstrap_from(input, test_vector, key, torus_base)
+            .unwrap()
+    }
+
+    pub fn try_functional_bootstrap_from(
+        &mut self,
+        input: &Self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) -> Result<()> {
+        let k = test_vector.k();
+        let upper_n = test_vector.upper_n();
+        check_eq("lower_n", k * upper_n, self.lower_n())?;
+        let mut rotated = unsafe { Trlwe::new_uninit(k, upper_n) };
+        rotated.try_functional_bootstrap_from(
+            input,
+            test_vector,
+            key,
+            torus_base,
+        )?;
+        self.set_from_trlwe(&rotated, 0);
+        Ok(())
+    }
+
+    pub fn functional_bootstrap(
+        &self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) -> Self {
+        self.try_functional_bootstrap(test_vector, key, torus_base)
+            .unwrap()
+    }
+
+    pub fn try_functional_bootstrap(
+        &self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) -> Result<Self> {
+        let lower_n = test_vector.k() * test_vector.upper_n();
+        let mut output = unsafe { Self::new_uninit(lower_n) };
+        output.try_functional_bootstrap_from(
+            self,
+            test_vector,
+            key,
+            torus_base,
+        )?;
+        Ok(output)
+    }
+
+    pub fn functional_bootstrap_ga_from(
+        &mut self,
+        input: &Self,
+        test_vector: &Trlwe,
+        key: &BootstrapGaKey,
+        torus_base: u32,
+    ) {
+        assert_eq!(self.lower_n(), key.k() * key.upper_n());
+        let k = test_vector.k();
+        let upper_n = test_vector.upper_n();
+        let mut rotated = unsafe { Trlwe::new_uninit(k, upper_n) };
+        rotated.functional_bootstrap_ga_from(
+            input,
+            test_vector,
+            key,
+            torus_base,
+        );
+        self.set_from_trlwe(&rotated, 0);
+    }
+
+    pub fn functional_bootstrap_ga(
+        &self,
+        test_vector: &Trlwe,
+        key: &BootstrapGaKey,
+        torus_base: u32,
+    ) -> Self {
+        let lower_n = test_vector.k() * test_vector.upper_n();
+        let mut output = unsafe { Self::new_uninit(lower_n) };
+        output.functional_bootstrap_ga_from(self, test_vector, key, torus_base);
+        output
+    }
+
+    pub fn programmable_bootstrap_from(
+        &mut self,
+        input: &Self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        precision: u32,
+        kappa: u32,
+        theta: u32,
+    ) {
+        assert!(precision > 0);
+        assert_eq!(input.lower_n(), key.lower_n());
+        assert_eq!(self.lower_n(), key.k() * key.upper_n());
+        assert_eq!(test_vector.k(), key.k());
+        assert_eq!(test_vector.upper_n(), key.upper_n());
+        unsafe {
+            mosfhet_sys::programmable_bootstrap(
+                self.ptr,
+                test_vector.as_ptr() as *mut _,
+                input.ptr,
+                key.as_ptr() as *mut _,
+                precision as i32,
+                kappa as i32,
+                theta as i32,
+            )
+        }
+    }
+
+    pub fn programmable_bootstrap(
+        &self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        precision: u32,
+        kappa: u32,
+        theta: u32,
+    ) -> Self {
+        let lower_n = test_vector.k() * test_vector.upper_n();
+        let mut output = unsafe { Self::new_uninit(lower_n) };
+        output.programmable_bootstrap_from(
+            self,
+            test_vector,
+            key,
+            precision,
+            kappa,
+            theta,
+        );
+        output
+    }
+
+    pub fn full_domain_functional_bootstrap_from(
+        &mut self,
+        input: &Self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        ks_key: &TlweKSKey,
+        precision: u32,
+    ) {
+        assert!(precision > 1);
+        assert_eq!(input.lower_n(), key.lower_n());
+        assert_eq!(self.lower_n(), key.k() * key.upper_n());
+        assert_eq!(test_vector.k(), key.k());
+        assert_eq!(test_vector.upper_n(), key.upper_n());
+        assert_eq!(ks_key.in_lower_n(), key.k() * key.upper_n());
+        assert_eq!(ks_key.out_lower_n(), key.lower_n());
+        unsafe {
+            mosfhet_sys::full_domain_functional_bootstrap(
+                self.ptr,
+                test_vector.as_ptr() as *mut _,
+                input.ptr,
+                key.as_ptr() as *mut _,
+                ks_key.as_ptr() as *mut _,
+                precision as i32,
+            )
+        }
+    }
+
+    pub fn full_domain_functional_bootstrap(
+        &self,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        ks_key: &TlweKSKey,
+        precision: u32,
+    ) -> Self {
+        let lower_n = test_vector.k() * test_vector.upper_n();
+        let mut output = unsafe { Self::new_uninit(lower_n) };
+        output.full_domain_functional_bootstrap_from(
+            self,
+            test_vector,
+            key,
+            ks_key,
+            precision,
+        );
+        output
+    }
+
+    pub fn full_domain_functional_bootstrap_clot21_from(
+        &mut self,
+        input: &Self,
+        test_vectors: &TrlweArray,
+        key: &BootstrapKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+        rl_key: &RelinearizationKey,
+        precision: u32,
+    ) {
+        assert!(precision > 1);
+        assert_eq!(test_vectors.len(), 2);
+        assert_eq!(input.lower_n(), key.lower_n());
+        assert_eq!(self.lower_n(), key.k() * key.upper_n());
+        assert_eq!(test_vectors.k(), key.k());
+        assert_eq!(test_vectors.upper_n(), key.upper_n());
+        assert_eq!(packing_ks_key.in_lower_n(), key.k() * key.upper_n());
+        assert_eq!(packing_ks_key.out_k(), 1);
+        assert_eq!(packing_ks_key.out_upper_n(), rl_key.upper_n());
+        unsafe {
+            mosfhet_sys::full_domain_functional_bootstrap_CLOT21(
+                self.ptr,
+                test_vectors.as_ptr() as *mut _,
+                input.ptr,
+                key.as_ptr() as *mut _,
+                packing_ks_key.as_ptr() as *mut _,
+                rl_key.as_ptr() as *mut _,
+                precision as i32,
+            )
+        }
+    }
+
+    pub fn full_domain_functional_bootstrap_clot21(
+        &self,
+        test_vectors: &TrlweArray,
+        key: &BootstrapKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+        rl_key: &RelinearizationKey,
+        precision: u32,
+    ) -> Self {
+        let lower_n = key.k() * key.upper_n();
+        let mut output = unsafe { Self::new_uninit(lower_n) };
+        output.full_domain_functional_bootstrap_clot21_from(
+            self,
+            test_vectors,
+            key,
+            packing_ks_key,
+            rl_key,
+            precision,
+        );
+        output
+    }
+
+    pub fn full_domain_functional_bootstrap_ks21_from(
+        &mut self,
+        input: &Self,
+        test_vector: &TorusPolynomial,
+        key: &BootstrapKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+        torus_base: u32,
+    ) {
+        assert!(torus_base > 1);
+        assert_eq!(torus_base % 2, 0);
+        assert_eq!(key.upper_n() % (key.l() * torus_base / 2), 0);
+        assert_eq!(input.lower_n(), key.lower_n());
+        assert_eq!(self.lower_n(), key.k() * key.upper_n());
+        assert_eq!(test_vector.upper_n(), 2 * key.upper_n());
+        assert_eq!(packing_ks_key.in_lower_n(), key.k() * key.upper_n());
+        assert_eq!(packing_ks_key.out_k(), key.k());
+        assert_eq!(packing_ks_key.out_upper_n(), key.upper_n());
+        unsafe {
+            mosfhet_sys::full_domain_functional_bootstrap_KS21(
+                self.ptr,
+                test_vector.as_ptr() as *mut _,
+                input.ptr,
+                key.as_ptr() as *mut _,
+                packing_ks_key.as_ptr() as *mut _,
+                torus_base as i32,
+            )
+        }
+    }
+
+    pub fn full_domain_functional_bootstrap_ks21(
+        &self,
+        test_vector: &TorusPolynomial,
+        key: &BootstrapKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+        torus_base: u32,
+    ) -> Self {
+        let lower_n = key.k() * key.upper_n();
+        let mut output = unsafe { Self::new_uninit(lower_n) };
+        output.full_domain_functional_bootstrap_ks21_from(
+            self,
+            test_vector,
+            key,
+            packing_ks_key,
+            torus_base,
+        );
         output
     }
+
+    pub fn multi_value_bootstrap(
+        &self,
+        test_vectors: &TrlweArray,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) -> TlweArray {
+        MultiValueBootstrap::new(self, key).eval_all(test_vectors, torus_base)
+    }
 }
 
 impl_load!(Tlwe => tlwe_load_new_sample(lower_n: u32));
-impl_save!(Tlwe => tlwe_save_sample);
+impl_save!(Tlwe => tlwe_save_sample(lower_n));
+impl_serde!(Tlwe);
 impl_ptrs!(Tlwe);
 
 unsafe impl Send for Tlwe {}
@@ -178,6 +622,10 @@ impl TlweArray {
         Self { len, ptr }
     }
 
+    fn sample_bytes(lower_n: u32) -> Option<usize> {
+        Tlwe::sample_bytes(lower_n)
+    }
+
     pub fn from_fn<F>(len: usize, key: &TlweKey, f: F) -> Self
     where
         F: Fn(usize) -> Torus,
@@ -239,9 +687,88 @@ impl TlweArray {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Tlwe> {
         self.as_slice_mut().iter_mut()
     }
+
+    pub fn extract_all(sample: &Trlwe) -> Self {
+        Self::extract_range(sample, 0..sample.upper_n() as usize)
+    }
+
+    pub fn extract_range(sample: &Trlwe, range: Range<usize>) -> Self {
+        let lower_n = sample.k() * sample.upper_n();
+        let mut output = unsafe { Self::new_uninit(range.len(), lower_n) };
+        output.extract_range_from(sample, range.start);
+        output
+    }
+
+    pub fn extract_range_from(&mut self, sample: &Trlwe, start: usize) {
+        assert!(start + self.len() <= sample.upper_n() as usize);
+        self.iter_mut()
+            .enumerate()
+            .for_each(|(i, p)| p.set_from_trlwe(sample, start + i));
+    }
+
+    pub fn extract_multi_value(sample: &Trlwe, amount: usize) -> Self {
+        let lower_n = sample.k() * sample.upper_n();
+        let mut output = unsafe { Self::new_uninit(amount, lower_n) };
+        output.extract_multi_value_from(sample);
+        output
+    }
+
+    pub fn extract_multi_value_from(&mut self, sample: &Trlwe) {
+        assert_eq!(self.lower_n(), sample.k() * sample.upper_n());
+        assert!(self.len() <= sample.upper_n() as usize);
+        unsafe {
+            mosfhet_sys::trlwe_mv_extract_tlwe(
+                self.ptr,
+                sample.as_ptr() as *mut _,
+                self.len() as i32,
+            )
+        }
+    }
+
+    pub fn from_packed_bootstrap(
+        input: &Tlwe,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        torus_base: u32,
+        num_luts: usize,
+    ) -> Self {
+        let lower_n = test_vector.k() * test_vector.upper_n();
+        let mut output = unsafe { Self::new_uninit(num_luts, lower_n) };
+        output.set_from_packed_bootstrap(input, test_vector, key, torus_base);
+        output
+    }
+
+    pub fn set_from_packed_bootstrap(
+        &mut self,
+        input: &Tlwe,
+        test_vector: &Trlwe,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) {
+        let num_luts = self.len() as u32;
+        let upper_n = test_vector.upper_n();
+        assert!(torus_base > 0 && num_luts > 0);
+        assert!(torus_base * num_luts <= upper_n);
+        assert_eq!(input.lower_n(), key.lower_n());
+        assert_eq!(test_vector.k(), key.k());
+        assert_eq!(upper_n, key.upper_n());
+        assert_eq!(self.lower_n(), key.k() * upper_n);
+        unsafe {
+            mosfhet_sys::multivalue_bootstrap_CLOT21(
+                self.as_ptr_mut() as *mut _,
+                test_vector.as_ptr() as *mut _,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+                torus_base as i32,
+                num_luts as i32,
+            )
+        }
+    }
 }
 
-impl_save_array!(TlweArray => tlwe_save_sample);
+impl_load_array!(TlweArray => tlwe_load_sample(lower_n: u32));
+impl_save_array!(TlweArray => tlwe_save_sample(lower_n));
+impl_serde!(TlweArray);
 impl_drop_array!(TlweArray => free_tlwe_array);
 impl_slice_array!(TlweArray);
 impl_ptrs!(TlweArray);
@@ -280,6 +807,7 @@ impl IndexMut<usize> for TlweArray {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::RawTorus;
 
     #[test]
     fn double_arithmetic() {
@@ -379,7 +907,7 @@ mod tests {
 
         let a = Tlwe::new(Torus::from_double(0.2), &key);
         a.save(path).unwrap();
-        let b = Tlwe::load(path, lower_n).unwrap();
+        let b = Tlwe::load(path).unwrap();
         std::fs::remove_file(path).unwrap();
 
         assert_eq!(a.lower_n(), b.lower_n());
@@ -387,6 +915,79 @@ mod tests {
         assert!(dbg!(d) <= (4.0 * sigma));
     }
 
+    #[test]
+    fn write_read() {
+        let lower_n = 632;
+        let sigma = 3.0517578125e-05;
+        let key = TlweKey::new_binary(lower_n, sigma);
+
+        let a = Tlwe::new(Torus::from_double(0.2), &key);
+        let arr = TlweArray::from_fn(4, &key, |i| {
+            Torus::from_unsigned(i as RawTorus, 3)
+        });
+        let mut bytes = Vec::new();
+        a.write_to(&mut bytes).unwrap();
+        arr.write_to(&mut bytes).unwrap();
+
+        let mut reader = bytes.as_slice();
+        let b = Tlwe::read_from(&mut reader).unwrap();
+        let brr = TlweArray::read_from(&mut reader).unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(b.lower_n(), lower_n);
+        assert_eq!((brr.len(), brr.lower_n()), (4, lower_n));
+        assert_eq!(a.phase(&key), b.phase(&key));
+        for (x, y) in arr.iter().zip(brr.iter()) {
+            assert_eq!(x.phase(&key), y.phase(&key));
+        }
+
+        let mut reader = &bytes[..100];
+        assert!(Tlwe::read_from(&mut reader).is_err());
+        let mut reader = bytes.as_slice();
+        assert!(TlweArray::read_from(&mut reader).is_err());
+    }
+
+    #[test]
+    fn array_read_invalid() {
+        use crate::common::format::{self, Tag};
+        let read = |params: [u64; 2], samples: usize| {
+            let mut bytes = Vec::new();
+            let payload = vec![0; samples * 3 * size_of::<RawTorus>()];
+            format::write_object(&mut bytes, Tag::TlweArray, &params, |p| {
+                p.extend_from_slice(&payload);
+                Ok(())
+            })
+            .unwrap();
+            TlweArray::read_from(&mut bytes.as_slice())
+        };
+        assert_eq!(read([2, 2], 2).unwrap().len(), 2);
+        for (params, samples) in [
+            ([(1 << 31) + 1, 2], 1),
+            ([0, 2], 0),
+            ([1, 0], 1),
+            ([1, 1 << 32], 1),
+            ([3, 2], 2),
+        ] {
+            assert!(matches!(
+                read(params, samples),
+                Err(crate::Error::MalformedFile(_))
+            ));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let lower_n = 632;
+        let sigma = 3.0517578125e-05;
+        let key = TlweKey::new_binary(lower_n, sigma);
+
+        let a = Tlwe::new(Torus::from_double(0.2), &key);
+        let json = serde_json::to_string(&a).unwrap();
+        let b: Tlwe = serde_json::from_str(&json).unwrap();
+        assert_eq!(a.lower_n(), b.lower_n());
+        assert_eq!(a.phase(&key), b.phase(&key));
+    }
+
     #[test]
     fn array_sum() {
         let lower_n = 632;
@@ -429,6 +1030,130 @@ mod tests {
         assert_eq!(tlwe.phase(&tlwe_key).into_unsigned(log_scale), 42);
     }
 
+    #[test]
+    fn extract_array() {
+        use crate::common::RawTorus;
+        use crate::poly::TorusPolynomial;
+        use crate::trlwe::TrlweKey;
+        let upper_n = 1024;
+        let k = 1;
+        let sigma = 5.51342964172363e-08;
+        let log_scale = 11;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            Torus::from_unsigned(i as RawTorus, log_scale)
+        });
+        let trlwe = Trlwe::new(poly, &trlwe_key);
+        let all = TlweArray::extract_all(&trlwe);
+        assert_eq!(all.len(), upper_n as usize);
+        for (i, tlwe) in all.iter().enumerate() {
+            let v = tlwe.phase(&tlwe_key).into_unsigned(log_scale);
+            assert_eq!(v, i as RawTorus);
+        }
+        let range = TlweArray::extract_range(&trlwe, 40..48);
+        assert_eq!(range.len(), 8);
+        for (i, tlwe) in range.iter().enumerate() {
+            let v = tlwe.phase(&tlwe_key).into_unsigned(log_scale);
+            assert_eq!(v, 40 + i as RawTorus);
+        }
+        let mv = TlweArray::extract_multi_value(&trlwe, 6);
+        let mask = (1 << log_scale) - 1;
+        for (i, tlwe) in mv.iter().enumerate() {
+            let v = tlwe.phase(&tlwe_key).into_unsigned(log_scale);
+            let expected = if i < 3 {
+                i as RawTorus
+            } else {
+                (upper_n as RawTorus - 1 - (i as RawTorus - 3)).wrapping_neg()
+                    & mask
+            };
+            assert_eq!(v, expected);
+        }
+    }
+
+    #[test]
+    fn extract_scaling() {
+        use crate::poly::TorusPolynomial;
+        use crate::trlwe::TrlweKey;
+        let upper_n = 1024;
+        let k = 1;
+        let sigma = 5.51342964172363e-08;
+        let log_scale = 8;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            let v = if i < upper_n as usize / 2 { 1 } else { 0 };
+            Torus::from_unsigned(v, log_scale)
+        });
+        let trlwe = Trlwe::new(poly, &trlwe_key);
+        let scaled = Tlwe::from_trlwe_scaling(&trlwe, 8);
+        assert_eq!(scaled.phase(&tlwe_key).into_unsigned(log_scale), 4);
+        let mut acc = Tlwe::zero_noiseless(tlwe_key.lower_n());
+        acc.add_assign_trlwe_scaling(&trlwe, 8);
+        assert_eq!(acc.phase(&tlwe_key).into_unsigned(log_scale), 4);
+        acc.add_assign_trlwe_scaling(&trlwe, 4);
+        assert_eq!(acc.phase(&tlwe_key).into_unsigned(log_scale), 6);
+        acc.sub_assign_trlwe_scaling(&trlwe, 16);
+        assert_eq!(acc.phase(&tlwe_key).into_unsigned(log_scale), 254);
+        // The window is [-scale/2, scale/2), as for the add and sub variants
+        for (index, expected) in [(upper_n - 1, 255), (4, 0), (3, 1)] {
+            let poly = TorusPolynomial::from_fn(upper_n, |i| {
+                let v = if i == index as usize { 1 } else { 0 };
+                Torus::from_unsigned(v, log_scale)
+            });
+            let trlwe = Trlwe::new(poly, &trlwe_key);
+            let scaled = Tlwe::from_trlwe_scaling(&trlwe, 8);
+            let v = scaled.phase(&tlwe_key).into_unsigned(log_scale);
+            assert_eq!(v, expected);
+        }
+    }
+
+    #[test]
+    fn fallible() {
+        use crate::poly::TorusPolynomial;
+        use crate::trlwe::TrlweKey;
+        use crate::Error;
+        let upper_n = 1024;
+        let k = 1;
+        let sigma = 5.51342964172363e-08;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let key = TlweKey::new_binary(630, sigma);
+        let a = Tlwe::new(Torus::from_double(0.2), &key);
+        let b = Tlwe::zero_noiseless(upper_n);
+        assert!(matches!(
+            a.try_add(&b),
+            Err(Error::ParameterMismatch {
+                name: "lower_n",
+                expected: 630,
+                found: 1024,
+            })
+        ));
+        let poly = TorusPolynomial::from_fn(upper_n, |_| Torus::MIN);
+        let trlwe = Trlwe::new(poly, &trlwe_key);
+        assert!(Tlwe::try_from_trlwe_scaling(&trlwe, upper_n).is_ok());
+        assert!(matches!(
+            Tlwe::try_from_trlwe_scaling(&trlwe, upper_n + 1),
+            Err(Error::InvalidScale { .. })
+        ));
+    }
+
+    #[test]
+    fn scale() {
+        let lower_n = 630;
+        let sigma = 3.0517578125e-05;
+        let log_scale = 6;
+        let key = TlweKey::new_binary(lower_n, sigma);
+        let a = Tlwe::new(Torus::from_unsigned(3, log_scale), &key);
+        let b = Tlwe::new(Torus::from_unsigned(5, log_scale), &key);
+        let c = a.scale(7);
+        assert_eq!(c.phase(&key).into_unsigned(log_scale), 21);
+        let c = a.scale(2u64.wrapping_neg());
+        assert_eq!(c.phase(&key).into_unsigned(log_scale), 58);
+        let mut c = a.scale(2);
+        c.scale_add_assign(&b, 3);
+        assert_eq!(c.phase(&key).into_unsigned(log_scale), 21);
+    }
+
     #[test]
     fn key_switch() {
         let lower_n_1 = 630;
@@ -444,4 +1169,205 @@ mod tests {
         let tlwe_2 = tlwe_1.key_switch(&tlwe_ks_key);
         assert_eq!(tlwe_2.phase(&tlwe_key_2).into_unsigned(3), 1);
     }
+
+    #[test]
+    fn functional_bootstrap() {
+        use crate::common::RawTorus;
+        use crate::poly::TorusPolynomial;
+        use crate::trgsw::TrgswKey;
+        use crate::trlwe::TrlweKey;
+        let lower_n = 744;
+        let sigma_1 = 7.747831515176779e-06;
+        let upper_n = 2048;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 1;
+        let bg_bit = 23;
+        let torus_base = 4;
+        let log_scale = 4;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        for unfolding in [1, 2] {
+            let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, unfolding);
+            let slot_size = (upper_n / torus_base) as usize;
+            let poly = TorusPolynomial::from_fn(upper_n, |i| {
+                let x = (i / slot_size) as RawTorus;
+                Torus::from_unsigned((x * x + 1) % 16, log_scale)
+            });
+            let tv = Trlwe::new_noiseless(poly, k, upper_n);
+            for x in 0..torus_base as RawTorus {
+                let tlwe = Tlwe::new(Torus::from_unsigned(x, 3), &tlwe_key);
+                let r = tlwe.functional_bootstrap(&tv, &bk_key, torus_base);
+                assert_eq!(r.lower_n(), k * upper_n);
+                assert_eq!(
+                    r.phase(&tlwe_key_out).into_unsigned(log_scale),
+                    (x * x + 1) % 16
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn functional_bootstrap_ga() {
+        use crate::common::RawTorus;
+        use crate::poly::TorusPolynomial;
+        use crate::trgsw::TrgswKey;
+        use crate::trlwe::TrlweKey;
+        let lower_n = 744;
+        let sigma_1 = 7.747831515176779e-06;
+        let upper_n = 1024;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 1;
+        let bg_bit = 23;
+        let torus_base = 4;
+        let log_scale = 4;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapGaKey::new(&tlwe_key, &trgsw_key);
+        let slot_size = (upper_n / torus_base) as usize;
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            let x = (i / slot_size) as RawTorus;
+            Torus::from_unsigned((x * x + 1) % 16, log_scale)
+        });
+        let tv = Trlwe::new_noiseless(poly, k, upper_n);
+        for x in 0..torus_base as RawTorus {
+            let tlwe = Tlwe::new(Torus::from_unsigned(x, 3), &tlwe_key);
+            let r = tlwe.functional_bootstrap_ga(&tv, &bk_key, torus_base);
+            assert_eq!(r.lower_n(), k * upper_n);
+            assert_eq!(
+                r.phase(&tlwe_key_out).into_unsigned(log_scale),
+                (x * x + 1) % 16
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn functional_bootstrap_ga_smaller_output() {
+        use crate::trgsw::TrgswKey;
+        use crate::trlwe::TrlweKey;
+        let upper_n = 256;
+        let tlwe_key = TlweKey::new_binary(16, 3.0517578125e-05);
+        let trlwe_key = TrlweKey::new(upper_n, 1, 2.2148688116005568e-16);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 1, 23);
+        let bk_key = BootstrapGaKey::new(&tlwe_key, &trgsw_key);
+        let tv = Trlwe::zeroed_noiseless(1, upper_n);
+        let tlwe = Tlwe::new(Torus::from_unsigned(0, 3), &tlwe_key);
+        let mut output = unsafe { Tlwe::new_uninit(upper_n / 2) };
+        output.functional_bootstrap_ga_from(&tlwe, &tv, &bk_key, 4);
+    }
+
+    #[test]
+    fn programmable_bootstrap() {
+        use crate::common::RawTorus;
+        use crate::trgsw::TrgswKey;
+        use crate::trlwe::TrlweKey;
+        let lower_n = 64;
+        let sigma_1 = 3.0517578125e-05;
+        let upper_n = 256;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 4;
+        let bg_bit = 10;
+        let precision = 3;
+        let torus_base = 1 << (precision - 1);
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let f = |x: RawTorus| (x * x + 1) % 8;
+        let tv = Trlwe::from_lut_fn(k, upper_n, torus_base, |x| {
+            Torus::from_unsigned(f(x as RawTorus), 3)
+        });
+        for (kappa, theta) in [(0, 0), (1, 0), (1, 1)] {
+            let log_scale = (precision + kappa) as usize;
+            for x in 0..(1 << log_scale) {
+                let m = x & ((1 << precision) - 1);
+                if m >= torus_base as RawTorus {
+                    continue;
+                }
+                let tlwe =
+                    Tlwe::new(Torus::from_unsigned(x, log_scale), &tlwe_key);
+                let r = tlwe.programmable_bootstrap(
+                    &tv, &bk_key, precision, kappa, theta,
+                );
+                assert_eq!(r.phase(&tlwe_key_out).into_unsigned(3), f(m));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn programmable_bootstrap_smaller_output() {
+        use crate::trgsw::TrgswKey;
+        use crate::trlwe::TrlweKey;
+        let upper_n = 256;
+        let tlwe_key = TlweKey::new_binary(16, 3.0517578125e-05);
+        let trlwe_key = TrlweKey::new(upper_n, 1, 2.2148688116005568e-16);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 4, 10);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let tv = Trlwe::zeroed_noiseless(1, upper_n);
+        let tlwe = Tlwe::new(Torus::from_unsigned(0, 3), &tlwe_key);
+        let mut output = unsafe { Tlwe::new_uninit(upper_n / 2) };
+        output.programmable_bootstrap_from(&tlwe, &tv, &bk_key, 3, 0, 0);
+    }
+
+    #[test]
+    fn full_domain_functional_bootstrap() {
+        use crate::common::RawTorus;
+        use crate::trgsw::TrgswKey;
+        use crate::trlwe::TrlweKey;
+        let lower_n = 64;
+        let sigma_1 = 3.0517578125e-05;
+        let upper_n = 256;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 4;
+        let bg_bit = 10;
+        let precision = 3;
+        let torus_base = 1 << precision;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let ks_key = TlweKSKey::new(&tlwe_key_out, &tlwe_key, 4, 4);
+        let packing_ks_key =
+            TrlwePacking1KSKey::new(&tlwe_key_out, &trlwe_key, 7, 4);
+        let rl_key = RelinearizationKey::new(&trlwe_key, 2, 20);
+        let f = |x: usize| (x * x + 3) as RawTorus % 8;
+        let g = |x: usize| Torus::from_unsigned(f(x), 3);
+        let tv = Trlwe::from_full_domain_lut_fn(k, upper_n, precision, g);
+        let tvs = TrlweArray::from_clot21_lut_fn(k, upper_n, precision, g);
+        let poly = TorusPolynomial::from_ks21_lut_fn(upper_n, torus_base, g);
+        for x in 0..torus_base as usize {
+            let m = Torus::from_unsigned(x as RawTorus, precision as usize);
+            let tlwe = Tlwe::new(m, &tlwe_key);
+            let r = tlwe.full_domain_functional_bootstrap(
+                &tv, &bk_key, &ks_key, precision,
+            );
+            assert_eq!(r.phase(&tlwe_key_out).into_unsigned(3), f(x));
+            let r = tlwe.full_domain_functional_bootstrap_clot21(
+                &tvs,
+                &bk_key,
+                &packing_ks_key,
+                &rl_key,
+                precision,
+            );
+            assert_eq!(r.phase(&tlwe_key_out).into_unsigned(3), f(x));
+            let r = tlwe.full_domain_functional_bootstrap_ks21(
+                &poly,
+                &bk_key,
+                &packing_ks_key,
+                torus_base,
+            );
+            assert_eq!(r.phase(&tlwe_key_out).into_unsigned(3), f(x));
+        }
+    }
 }
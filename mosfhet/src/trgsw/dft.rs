@@ -1,10 +1,14 @@
 use std::ops::{Index, IndexMut};
 
+use crate::bootstrap::BootstrapKey;
 use crate::common::macros::*;
-use crate::common::Torus;
+use crate::common::{format, Torus};
+use crate::error::{check, check_eq};
 use crate::poly::DftPolynomial;
+use crate::tlwe::{Tlwe, TlweArray};
 use crate::trgsw::Trgsw;
-use crate::trlwe::{Trlwe, TrlweDft};
+use crate::trlwe::{Trlwe, TrlweDft, TrlwePacking1KSKey, TrlwePrivKSKey};
+use crate::Result;
 
 use super::TrgswKey;
 
@@ -30,6 +34,16 @@ impl TrgswDft {
         }
     }
 
+    fn sample_bytes(
+        l: u32,
+        _bg_bit: u32,
+        k: u32,
+        upper_n: u32,
+    ) -> Option<usize> {
+        let (l, k, upper_n) = (l as usize, k as usize, upper_n as usize);
+        format::bytes::<f64>(&[l, k + 1, k + 1, upper_n])
+    }
+
     pub fn from_trgsw(sample: &Trgsw) -> Self {
         let l = sample.l();
         let bg_bit = sample.bg_bit();
@@ -46,6 +60,41 @@ impl TrgswDft {
         }
     }
 
+    pub fn from_tlwe_circuit_bootstrap(
+        input: &Tlwe,
+        key: &BootstrapKey,
+        priv_ks_key: &TrlwePrivKSKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+        l: u32,
+        bg_bit: u32,
+    ) -> Self {
+        Self::from_trgsw(&Trgsw::from_tlwe_circuit_bootstrap(
+            input,
+            key,
+            priv_ks_key,
+            packing_ks_key,
+            l,
+            bg_bit,
+        ))
+    }
+
+    pub fn set_from_tlwe_circuit_bootstrap(
+        &mut self,
+        input: &Tlwe,
+        key: &BootstrapKey,
+        priv_ks_key: &TrlwePrivKSKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+    ) {
+        self.set_from_trgsw(&Trgsw::from_tlwe_circuit_bootstrap(
+            input,
+            key,
+            priv_ks_key,
+            packing_ks_key,
+            self.l(),
+            self.bg_bit(),
+        ));
+    }
+
     pub fn l(&self) -> u32 {
         unsafe { (*self.ptr).l as u32 }
     }
@@ -98,6 +147,95 @@ impl TrgswDft {
         output
     }
 
+    pub fn mul_from(&mut self, lhs: &Self, rhs: &Self) {
+        self.try_mul_from(lhs, rhs).unwrap()
+    }
+
+    pub fn try_mul_from(&mut self, lhs: &Self, rhs: &Self) -> Result<()> {
+        self.check_params(lhs)?;
+        lhs.check_params(rhs)?;
+        unsafe { mosfhet_sys::trgsw_mul_DFT2(self.ptr, lhs.ptr, rhs.ptr) }
+        Ok(())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        self.try_mul(other).unwrap()
+    }
+
+    pub fn try_mul(&self, other: &Self) -> Result<Self> {
+        let l = self.l();
+        let bg_bit = self.bg_bit();
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.try_mul_from(self, other)?;
+        Ok(output)
+    }
+
+    pub fn mul_trgsw_dft(lhs: &Trgsw, rhs: &TrgswDft) -> Self {
+        Self::try_mul_trgsw_dft(lhs, rhs).unwrap()
+    }
+
+    pub fn try_mul_trgsw_dft(lhs: &Trgsw, rhs: &TrgswDft) -> Result<Self> {
+        let l = rhs.l();
+        let bg_bit = rhs.bg_bit();
+        let k = rhs.k();
+        let upper_n = rhs.upper_n();
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.try_mul_trgsw_dft_from(lhs, rhs)?;
+        Ok(output)
+    }
+
+    pub fn mul_trgsw_dft_from(&mut self, lhs: &Trgsw, rhs: &TrgswDft) {
+        self.try_mul_trgsw_dft_from(lhs, rhs).unwrap()
+    }
+
+    pub fn try_mul_trgsw_dft_from(
+        &mut self,
+        lhs: &Trgsw,
+        rhs: &TrgswDft,
+    ) -> Result<()> {
+        self.check_params(rhs)?;
+        check_eq("l", rhs.l(), lhs.l())?;
+        check_eq("bg_bit", rhs.bg_bit(), lhs.bg_bit())?;
+        check_eq("k", rhs.k(), lhs.k())?;
+        check_eq("upper_n", rhs.upper_n(), lhs.upper_n())?;
+        unsafe {
+            mosfhet_sys::trgsw_mul_DFT(
+                self.ptr,
+                lhs.as_ptr() as *mut _,
+                rhs.ptr,
+            )
+        }
+        Ok(())
+    }
+
+    pub fn not_from(&mut self, input: &Self) {
+        assert_eq!(self.l(), input.l());
+        assert_eq!(self.bg_bit(), input.bg_bit());
+        assert_eq!(self.k(), input.k());
+        assert_eq!(self.upper_n(), input.upper_n());
+        let one = Trgsw::new_noiseless(
+            Torus(1),
+            input.l(),
+            input.bg_bit(),
+            input.k(),
+            input.upper_n(),
+        );
+        self.set_from_trgsw(&one);
+        unsafe { mosfhet_sys::trgsw_DFT_sub(self.ptr, self.ptr, input.ptr) }
+    }
+
+    pub fn not(&self) -> Self {
+        let l = self.l();
+        let bg_bit = self.bg_bit();
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.not_from(self);
+        output
+    }
+
     pub fn mul_add_assign_poly(&mut self, other: &Self, poly: &DftPolynomial) {
         unsafe {
             mosfhet_sys::trgsw_DFT_mul_addto_by_polynomial(
@@ -109,56 +247,105 @@ impl TrgswDft {
     }
 
     pub fn cmux(&self, in0: &mut Trlwe, in1: &mut Trlwe) {
+        self.try_cmux(in0, in1).unwrap()
+    }
+
+    pub fn try_cmux(&self, in0: &mut Trlwe, in1: &mut Trlwe) -> Result<()> {
+        self.check_trlwe(in0)?;
+        self.check_trlwe(in1)?;
         in1.sub_assign(in0);
         in1.set_from_dft(&TrlweDft::mul_trlwe_dft(in1, self));
         in0.add_assign(in1);
+        Ok(())
     }
 
     pub fn cmux_vectored(&self, in_: &mut [Trlwe], stride: usize) {
+        self.try_cmux_vectored(in_, stride).unwrap()
+    }
+
+    pub fn try_cmux_vectored(
+        &self,
+        in_: &mut [Trlwe],
+        stride: usize,
+    ) -> Result<()> {
+        check(stride > 0, "stride", stride as u64)?;
+        for sample in in_.iter() {
+            self.check_trlwe(sample)?;
+        }
+
         let chunk_size = stride + stride;
         let spare_items = in_.len() % chunk_size;
 
         for chunk in in_.chunks_exact_mut(chunk_size) {
             let (lower, upper) = chunk.split_at_mut(stride);
-            self.cmux(&mut lower[0], &mut upper[0]);
+            self.try_cmux(&mut lower[0], &mut upper[0])?;
         }
 
         if spare_items > stride {
             let offset = in_.len() - spare_items;
             let chunk = &mut in_[offset..];
             let (lower, upper) = chunk.split_at_mut(stride);
-            self.cmux(&mut lower[0], &mut upper[0]);
+            self.try_cmux(&mut lower[0], &mut upper[0])?;
         }
+        Ok(())
     }
 
     pub fn cdemux(&self, in0: &mut Trlwe, in1: &mut Trlwe) {
+        self.try_cdemux(in0, in1).unwrap()
+    }
+
+    pub fn try_cdemux(&self, in0: &mut Trlwe, in1: &mut Trlwe) -> Result<()> {
+        self.check_trlwe(in0)?;
+        self.check_trlwe(in1)?;
         in1.add_assign(in0);
         in1.set_from_dft(&TrlweDft::mul_trlwe_dft(in1, self));
         in0.sub_assign(in1);
+        Ok(())
     }
 
     pub fn cdemux_vectored(&self, in_: &mut [Trlwe], stride: usize) {
+        self.try_cdemux_vectored(in_, stride).unwrap()
+    }
+
+    pub fn try_cdemux_vectored(
+        &self,
+        in_: &mut [Trlwe],
+        stride: usize,
+    ) -> Result<()> {
+        check(stride > 0, "stride", stride as u64)?;
+        for sample in in_.iter() {
+            self.check_trlwe(sample)?;
+        }
+
         let chunk_size = stride + stride;
         let spare_items = in_.len() % chunk_size;
 
         for chunk in in_.chunks_exact_mut(chunk_size) {
             let (lower, upper) = chunk.split_at_mut(stride);
-            self.cdemux(&mut lower[0], &mut upper[0]);
+            self.try_cdemux(&mut lower[0], &mut upper[0])?;
         }
 
         if spare_items > stride {
             let offset = in_.len() - spare_items;
             let chunk = &mut in_[offset..];
             let (lower, upper) = chunk.split_at_mut(stride);
-            self.cdemux(&mut lower[0], &mut upper[0]);
+            self.try_cdemux(&mut lower[0], &mut upper[0])?;
         }
+        Ok(())
     }
 
     pub fn cswap(&self, in0: &mut Trlwe, in1: &mut Trlwe) {
+        self.try_cswap(in0, in1).unwrap()
+    }
+
+    pub fn try_cswap(&self, in0: &mut Trlwe, in1: &mut Trlwe) -> Result<()> {
+        self.check_trlwe(in0)?;
+        self.check_trlwe(in1)?;
         let mut delta = Trlwe::sub(in0, in1);
         delta.set_from_dft(&TrlweDft::mul_trlwe_dft(&delta, self));
         in0.sub_assign(&delta);
         in1.add_assign(&delta);
+        Ok(())
     }
 
     pub fn cswap_vectored(
@@ -167,7 +354,21 @@ impl TrgswDft {
         offset: usize,
         stride: usize,
     ) {
-        assert!(offset < stride);
+        self.try_cswap_vectored(in_, offset, stride).unwrap()
+    }
+
+    pub fn try_cswap_vectored(
+        &self,
+        in_: &mut [Trlwe],
+        offset: usize,
+        stride: usize,
+    ) -> Result<()> {
+        check(offset < stride, "offset", offset as u64)?;
+        check(offset <= in_.len(), "offset", offset as u64)?;
+        for sample in in_.iter() {
+            self.check_trlwe(sample)?;
+        }
+
         let chunk_size = stride + stride;
         let spare_left = offset;
         let spare_right = (in_.len() - spare_left) % chunk_size;
@@ -175,20 +376,33 @@ impl TrgswDft {
 
         for chunk in offset_slice.chunks_exact_mut(chunk_size) {
             let (lower, upper) = chunk.split_at_mut(stride);
-            self.cswap(&mut lower[0], &mut upper[0]);
+            self.try_cswap(&mut lower[0], &mut upper[0])?;
         }
 
         if spare_right > stride {
             let offset = in_.len() - spare_right;
             let chunk = &mut in_[offset..];
             let (lower, upper) = chunk.split_at_mut(stride);
-            self.cswap(&mut lower[0], &mut upper[0]);
+            self.try_cswap(&mut lower[0], &mut upper[0])?;
         } else if spare_left + spare_right > stride {
             let offset = in_.len() - spare_right;
             let (lower, upper) = in_.split_at_mut(offset);
             let wrap_idx = stride - spare_right;
-            self.cswap(&mut upper[0], &mut lower[wrap_idx]);
+            self.try_cswap(&mut upper[0], &mut lower[wrap_idx])?;
         }
+        Ok(())
+    }
+
+    fn check_params(&self, other: &Self) -> Result<()> {
+        check_eq("l", self.l(), other.l())?;
+        check_eq("bg_bit", self.bg_bit(), other.bg_bit())?;
+        check_eq("k", self.k(), other.k())?;
+        check_eq("upper_n", self.upper_n(), other.upper_n())
+    }
+
+    fn check_trlwe(&self, sample: &Trlwe) -> Result<()> {
+        check_eq("k", self.k(), sample.k())?;
+        check_eq("upper_n", self.upper_n(), sample.upper_n())
     }
 }
 
@@ -197,10 +411,11 @@ impl_load!(
         l: u32,
         bg_bit: u32,
         k: u32,
-        lower_n: u32,
+        upper_n: u32,
     )
 );
-impl_save!(TrgswDft => trgsw_save_DFT_sample);
+impl_save!(TrgswDft => trgsw_save_DFT_sample(l, bg_bit, k, upper_n));
+impl_serde!(TrgswDft);
 impl_drop!(TrgswDft => free_trgsw);
 impl_ptrs!(TrgswDft);
 
@@ -246,6 +461,15 @@ impl TrgswDftArray {
         Self { len, ptr }
     }
 
+    fn sample_bytes(
+        l: u32,
+        bg_bit: u32,
+        k: u32,
+        upper_n: u32,
+    ) -> Option<usize> {
+        TrgswDft::sample_bytes(l, bg_bit, k, upper_n)
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -292,6 +516,68 @@ impl TrgswDftArray {
         Self::from_fn(len, key, |_| (m, e))
     }
 
+    pub fn from_fn_noiseless<F>(
+        len: usize,
+        l: u32,
+        bg_bit: u32,
+        k: u32,
+        upper_n: u32,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(usize) -> (Torus, u32),
+    {
+        let mut output =
+            unsafe { Self::new_uninit(len, l, bg_bit, k, upper_n) };
+        let mut tmp = unsafe { Trgsw::new_uninit(l, bg_bit, k, upper_n) };
+        output
+            .as_slice_mut()
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, p)| {
+                let (m, e) = f(i);
+                tmp.set_noiseless_monomial(m, e);
+                p.set_from_trgsw(&tmp);
+            });
+        output
+    }
+
+    pub fn from_elem_noiseless(
+        len: usize,
+        l: u32,
+        bg_bit: u32,
+        k: u32,
+        upper_n: u32,
+        m: Torus,
+        e: u32,
+    ) -> Self {
+        Self::from_fn_noiseless(len, l, bg_bit, k, upper_n, |_| (m, e))
+    }
+
+    pub fn from_tlwe_circuit_bootstrap(
+        inputs: &TlweArray,
+        key: &BootstrapKey,
+        priv_ks_key: &TrlwePrivKSKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+        l: u32,
+        bg_bit: u32,
+    ) -> Self {
+        let len = inputs.len();
+        let k = key.k();
+        let upper_n = key.upper_n();
+        let mut output =
+            unsafe { Self::new_uninit(len, l, bg_bit, k, upper_n) };
+        output.iter_mut().zip(inputs.iter()).for_each(|(o, input)| {
+            o.set_from_tlwe_circuit_bootstrap(
+                input,
+                key,
+                priv_ks_key,
+                packing_ks_key,
+            )
+        });
+        output
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &TrgswDft> {
         self.as_slice().iter()
     }
@@ -306,10 +592,11 @@ impl_load_array!(
         l: u32,
         bg_bit: u32,
         k: u32,
-        lower_n: u32,
+        upper_n: u32,
     )
 );
-impl_save_array!(TrgswDftArray => trgsw_save_DFT_sample);
+impl_save_array!(TrgswDftArray => trgsw_save_DFT_sample(l, bg_bit, k, upper_n));
+impl_serde!(TrgswDftArray);
 impl_drop_array!(TrgswDftArray => free_trgsw_array);
 impl_slice_array!(TrgswDftArray);
 impl_ptrs!(TrgswDftArray);
@@ -350,3 +637,248 @@ impl IndexMut<usize> for TrgswDftArray {
         self.as_slice_mut().index_mut(index)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn circuit_bootstrap() {
+        let lower_n = 128;
+        let sigma_1 = 9.5367431640625e-07;
+        let upper_n = 512;
+        let sigma_2 = 9.094947017729282e-13;
+        let k = 1;
+        let bk_l = 3;
+        let bk_bg_bit = 10;
+        let l = 2;
+        let bg_bit = 10;
+        let t = 16;
+        let base_bit = 2;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let trgsw_key = TrgswKey::new(&trlwe_key, bk_l, bk_bg_bit);
+        let tlwe_key_out = TlweKey::from_trlwe_key(&trlwe_key);
+        let bk_key = BootstrapKey::new(&tlwe_key, &trgsw_key, 1);
+        let priv_ks_key =
+            TrlwePrivKSKey::new(&tlwe_key_out, &trlwe_key, t, base_bit);
+        let packing_ks_key =
+            TrlwePacking1KSKey::new(&tlwe_key_out, &trlwe_key, t, base_bit);
+        let inputs = TlweArray::from_fn(2, &tlwe_key, |i| {
+            Torus::from_unsigned(i as RawTorus, 2)
+        });
+        let selectors = TrgswDftArray::from_tlwe_circuit_bootstrap(
+            &inputs,
+            &bk_key,
+            &priv_ks_key,
+            &packing_ks_key,
+            l,
+            bg_bit,
+        );
+        for (bit, selector) in selectors.iter().enumerate() {
+            let mut in0 = Trlwe::new(
+                TorusPolynomial::from_fn(upper_n, |i| {
+                    Torus::from_unsigned((i % 4) as RawTorus, 3)
+                }),
+                &trlwe_key,
+            );
+            let mut in1 = Trlwe::new(
+                TorusPolynomial::from_fn(upper_n, |i| {
+                    Torus::from_unsigned((i % 4 + 4) as RawTorus, 3)
+                }),
+                &trlwe_key,
+            );
+            selector.cmux(&mut in0, &mut in1);
+            for (i, v) in in0.phase(&trlwe_key).iter().enumerate() {
+                assert_eq!(v.into_unsigned(3), (i % 4 + 4 * bit) as RawTorus);
+            }
+        }
+    }
+
+    #[test]
+    fn monomial_samples() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 12;
+        let precision = 8;
+        let mask = (1 << precision) - 1;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let values = |i: usize| (i as RawTorus * 7 + 2) & mask;
+        let m = TorusPolynomial::from_fn(upper_n, |i| {
+            Torus::from_unsigned(values(i), precision)
+        });
+        let trlwe = Trlwe::new(m.clone(), &trlwe_key);
+        let exps = [0, 1, 5, 255, 256, 300, 511];
+        let noisy = TrgswDftArray::from_fn(exps.len(), &trgsw_key, |i| {
+            (Torus::from_raw(1), exps[i])
+        });
+        let noiseless = TrgswDftArray::from_fn_noiseless(
+            exps.len(),
+            l,
+            bg_bit,
+            k,
+            upper_n,
+            |i| (Torus::from_raw(1), exps[i]),
+        );
+        let exp = TrgswDft::from_trgsw(&Trgsw::new_exp(5, &trgsw_key));
+        let check = |c: &TrgswDft, e: u32| {
+            let out = Trlwe::from_dft(&TrlweDft::mul_trlwe_dft(&trlwe, c));
+            let expected = m.mul_by_xai(e);
+            for (v, x) in out.phase(&trlwe_key).iter().zip(expected.iter()) {
+                assert_eq!(
+                    v.into_unsigned(precision),
+                    x.into_unsigned(precision)
+                );
+            }
+        };
+        for (i, &e) in exps.iter().enumerate() {
+            check(&noisy[i], e);
+            check(&noiseless[i], e);
+        }
+        check(&exp, 5);
+    }
+
+    #[test]
+    fn internal_product() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let l = 3;
+        let bg_bit = 12;
+        let precision = 8;
+        let mask = (1 << precision) - 1;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
+        let values = |i: usize| (i as RawTorus * 3 + 1) & mask;
+        let m = TorusPolynomial::from_fn(upper_n, |i| {
+            Torus::from_unsigned(values(i), precision)
+        });
+        let trlwe = Trlwe::new(m, &trlwe_key);
+        let check = |c: &TrgswDft, bit: bool| {
+            let out = Trlwe::from_dft(&TrlweDft::mul_trlwe_dft(&trlwe, c));
+            for (i, v) in out.phase(&trlwe_key).iter().enumerate() {
+                let expected = if bit { values(i) } else { 0 };
+                assert_eq!(v.into_unsigned(precision), expected);
+            }
+        };
+        for a in [false, true] {
+            let ca = Trgsw::new(Torus::from_raw(a as RawTorus), 0, &trgsw_key);
+            let ca_dft = TrgswDft::from_trgsw(&ca);
+            check(&ca_dft.not(), !a);
+            check(&TrgswDft::from_trgsw(&Trgsw::from_dft(&ca_dft)), a);
+            check(&TrgswDft::from_trgsw(&ca.not()), !a);
+            for b in [false, true] {
+                let cb =
+                    Trgsw::new(Torus::from_raw(b as RawTorus), 0, &trgsw_key);
+                let cb_dft = TrgswDft::from_trgsw(&cb);
+                check(&ca_dft.mul(&cb_dft), a & b);
+                check(&TrgswDft::mul_trgsw_dft(&ca, &cb_dft), a & b);
+                check(&ca_dft.not().mul(&cb_dft), !a & b);
+                check(&TrgswDft::from_trgsw(&ca.naive_mul(&cb)), a & b);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn mul_from_smaller_output() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 3, 12);
+        let small_key = TrgswKey::new(&trlwe_key, 1, 12);
+        let c = TrgswDft::from_trgsw(&Trgsw::new(Torus::MIN, 0, &trgsw_key));
+        let small = Trgsw::new(Torus::MIN, 0, &small_key);
+        let mut output = TrgswDft::from_trgsw(&small);
+        output.mul_from(&c, &c);
+    }
+
+    #[test]
+    fn try_cmux_mismatch() {
+        let sigma = 2.2148688116005568e-16;
+        let trlwe_key = TrlweKey::new(256, 1, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 3, 12);
+        let c = TrgswDft::from_trgsw(&Trgsw::new(Torus::MIN, 0, &trgsw_key));
+        let mut in0 = Trlwe::zeroed_noiseless(1, 256);
+        let mut in1 = Trlwe::zeroed_noiseless(1, 512);
+        let result = c.try_cmux(&mut in0, &mut in1);
+        assert!(matches!(
+            result,
+            Err(crate::Error::ParameterMismatch {
+                name: "upper_n",
+                ..
+            })
+        ));
+        let mut in_ = vec![in0.clone(), in0];
+        let result = c.try_cmux_vectored(&mut in_, 0);
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidParameter { name: "stride", .. })
+        ));
+    }
+
+    #[test]
+    fn try_cswap_mismatch() {
+        let sigma = 2.2148688116005568e-16;
+        let trlwe_key = TrlweKey::new(256, 1, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 3, 12);
+        let c = TrgswDft::from_trgsw(&Trgsw::new(Torus::MIN, 0, &trgsw_key));
+        let mut in0 = Trlwe::zeroed_noiseless(1, 256);
+        let mut in1 = Trlwe::zeroed_noiseless(2, 256);
+        let result = c.try_cswap(&mut in0, &mut in1);
+        assert!(matches!(
+            result,
+            Err(crate::Error::ParameterMismatch { name: "k", .. })
+        ));
+        let result = c.try_cdemux(&mut in0, &mut in1);
+        assert!(matches!(
+            result,
+            Err(crate::Error::ParameterMismatch { name: "k", .. })
+        ));
+        let mut in_ = vec![in0.clone(), in0];
+        let result = c.try_cswap_vectored(&mut in_, 1, 1);
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidParameter { name: "offset", .. })
+        ));
+    }
+
+    #[test]
+    fn write_read() {
+        let sigma = 2.2148688116005568e-16;
+        let trlwe_key = TrlweKey::new(256, 2, sigma);
+        let trgsw_key = TrgswKey::new(&trlwe_key, 3, 10);
+        let trgsw = Trgsw::new(Torus::from_raw(1), 5, &trgsw_key);
+        let trgsw_dft = TrgswDft::from_trgsw(&trgsw);
+        let trgsws = TrgswArray::from_elem(3, &trgsw_key, Torus::MIN, 1);
+        let trgsw_dfts = TrgswDftArray::from_elem(3, &trgsw_key, Torus::MIN, 1);
+        let mut bytes = Vec::new();
+        trgsw.write_to(&mut bytes).unwrap();
+        trgsw_dft.write_to(&mut bytes).unwrap();
+        trgsws.write_to(&mut bytes).unwrap();
+        trgsw_dfts.write_to(&mut bytes).unwrap();
+        let mut reader = bytes.as_slice();
+        let mut again = Vec::new();
+        Trgsw::read_from(&mut reader)
+            .unwrap()
+            .write_to(&mut again)
+            .unwrap();
+        TrgswDft::read_from(&mut reader)
+            .unwrap()
+            .write_to(&mut again)
+            .unwrap();
+        TrgswArray::read_from(&mut reader)
+            .unwrap()
+            .write_to(&mut again)
+            .unwrap();
+        TrgswDftArray::read_from(&mut reader)
+            .unwrap()
+            .write_to(&mut again)
+            .unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(bytes, again);
+    }
+}
@@ -1,5 +1,8 @@
 use crate::common::macros::*;
+use crate::common::RawTorus;
+use crate::error::check;
 use crate::trlwe::TrlweKey;
+use crate::Result;
 
 #[repr(transparent)]
 pub struct TrgswKey {
@@ -8,15 +11,22 @@ pub struct TrgswKey {
 
 impl TrgswKey {
     pub fn new(trlwe_key: &TrlweKey, l: u32, bg_bit: u32) -> Self {
-        Self {
-            ptr: unsafe {
-                mosfhet_sys::trgsw_new_key(
-                    trlwe_key.as_ptr() as *mut _,
-                    l as i32,
-                    bg_bit as i32,
-                )
-            },
-        }
+        Self::try_new(trlwe_key, l, bg_bit).unwrap()
+    }
+
+    pub fn try_new(trlwe_key: &TrlweKey, l: u32, bg_bit: u32) -> Result<Self> {
+        check(l > 0, "l", l)?;
+        check(bg_bit > 0, "bg_bit", bg_bit)?;
+        let l_bits = l as u64 * bg_bit as u64;
+        check(l_bits <= RawTorus::BITS as u64, "l * bg_bit", l_bits)?;
+        let ptr = unsafe {
+            mosfhet_sys::trgsw_new_key(
+                trlwe_key.as_ptr() as *mut _,
+                l as i32,
+                bg_bit as i32,
+            )
+        };
+        Ok(Self { ptr })
     }
 
     pub fn l(&self) -> u32 {
@@ -40,8 +50,9 @@ impl TrgswKey {
     }
 }
 
-impl_load!(TrgswKey => trgsw_load_new_key);
-impl_save!(TrgswKey => trgsw_save_key);
+impl_load!(TrgswKey => trgsw_load_new_key; l, bg_bit, k, upper_n);
+impl_save!(TrgswKey => trgsw_save_key(l, bg_bit, k, upper_n));
+impl_serde!(TrgswKey);
 impl_drop!(TrgswKey => free_trgsw_key);
 impl_ptrs!(TrgswKey);
 
@@ -1,8 +1,13 @@
 use std::ops::{Index, IndexMut};
 
+use crate::bootstrap::BootstrapKey;
 use crate::common::macros::*;
-use crate::common::Torus;
-use crate::trgsw::TrgswKey;
+use crate::common::{format, RawTorus, Torus};
+use crate::error::check_eq;
+use crate::tlwe::Tlwe;
+use crate::trgsw::{TrgswDft, TrgswKey};
+use crate::trlwe::{TrlwePacking1KSKey, TrlwePrivKSKey};
+use crate::{Error, Result};
 
 #[repr(transparent)]
 pub struct Trgsw {
@@ -26,27 +31,72 @@ impl Trgsw {
         }
     }
 
+    fn sample_bytes(
+        l: u32,
+        _bg_bit: u32,
+        k: u32,
+        upper_n: u32,
+    ) -> Option<usize> {
+        let (l, k, upper_n) = (l as usize, k as usize, upper_n as usize);
+        format::bytes::<RawTorus>(&[l, k + 1, k + 1, upper_n])
+    }
+
     pub fn new(m: Torus, e: u32, key: &TrgswKey) -> Self {
-        Self {
+        Self::try_new(m, e, key).unwrap()
+    }
+
+    pub fn try_new(m: Torus, e: u32, key: &TrgswKey) -> Result<Self> {
+        let m = Self::scale(m)?;
+        Ok(Self {
             ptr: unsafe {
                 mosfhet_sys::trgsw_new_monomial_sample(
-                    m.0.try_into().unwrap(),
+                    m,
                     e as i32,
                     key.as_ptr() as *mut _,
                 )
             },
-        }
+        })
     }
 
     pub fn set(&mut self, m: Torus, e: u32, key: &TrgswKey) {
+        self.try_set(m, e, key).unwrap()
+    }
+
+    pub fn try_set(&mut self, m: Torus, e: u32, key: &TrgswKey) -> Result<()> {
+        let m = Self::scale(m)?;
         unsafe {
             mosfhet_sys::trgsw_monomial_sample(
                 self.ptr,
-                m.0.try_into().unwrap(),
+                m,
                 e as i32,
                 key.as_ptr() as *mut _,
             )
         }
+        Ok(())
+    }
+
+    fn scale(m: Torus) -> Result<i64> {
+        let scale = m.0 as i64;
+        if scale < 0 {
+            let bound = i64::MAX as u64;
+            return Err(Error::InvalidScale {
+                scale: scale as u64,
+                bound,
+            });
+        }
+        Ok(scale)
+    }
+
+    pub fn new_exp(e: u32, key: &TrgswKey) -> Self {
+        Self::try_new_exp(e, key).unwrap()
+    }
+
+    pub fn try_new_exp(e: u32, key: &TrgswKey) -> Result<Self> {
+        Self::try_new(Torus(1), e, key)
+    }
+
+    pub fn set_exp(&mut self, e: u32, key: &TrgswKey) {
+        self.set(Torus(1), e, key)
     }
 
     pub fn new_noiseless(
@@ -82,6 +132,30 @@ impl Trgsw {
         }
     }
 
+    pub fn new_noiseless_monomial(
+        m: Torus,
+        e: u32,
+        l: u32,
+        bg_bit: u32,
+        k: u32,
+        upper_n: u32,
+    ) -> Self {
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.set_noiseless_monomial(m, e);
+        output
+    }
+
+    pub fn set_noiseless_monomial(&mut self, m: Torus, e: u32) {
+        if e == 0 {
+            return self.set_noiseless(m);
+        }
+        let (l, bg_bit) = (self.l(), self.bg_bit());
+        let (k, upper_n) = (self.k(), self.upper_n());
+        let mut constant = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        constant.set_noiseless(m);
+        self.mul_by_xai_from(&constant, e);
+    }
+
     pub fn l(&self) -> u32 {
         unsafe { (*self.ptr).l as u32 }
     }
@@ -175,10 +249,130 @@ impl Trgsw {
             mosfhet_sys::trgsw_mul_by_xai_addto(self.ptr, source.ptr, a as i32)
         }
     }
+
+    pub fn naive_mul_from(&mut self, lhs: &Self, rhs: &Self) {
+        self.try_naive_mul_from(lhs, rhs).unwrap()
+    }
+
+    pub fn try_naive_mul_from(&mut self, lhs: &Self, rhs: &Self) -> Result<()> {
+        self.check_params(lhs)?;
+        lhs.check_params(rhs)?;
+        unsafe { mosfhet_sys::trgsw_naive_mul(self.ptr, lhs.ptr, rhs.ptr) }
+        Ok(())
+    }
+
+    pub fn naive_mul(&self, other: &Self) -> Self {
+        self.try_naive_mul(other).unwrap()
+    }
+
+    pub fn try_naive_mul(&self, other: &Self) -> Result<Self> {
+        let l = self.l();
+        let bg_bit = self.bg_bit();
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.try_naive_mul_from(self, other)?;
+        Ok(output)
+    }
+
+    pub fn not_from(&mut self, input: &Self) {
+        self.try_not_from(input).unwrap()
+    }
+
+    pub fn try_not_from(&mut self, input: &Self) -> Result<()> {
+        self.check_params(input)?;
+        self.set_noiseless(Torus(1));
+        unsafe { mosfhet_sys::trgsw_sub(self.ptr, self.ptr, input.ptr) }
+        Ok(())
+    }
+
+    pub fn not(&self) -> Self {
+        let l = self.l();
+        let bg_bit = self.bg_bit();
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.not_from(self);
+        output
+    }
+
+    pub fn from_dft(sample: &TrgswDft) -> Self {
+        let l = sample.l();
+        let bg_bit = sample.bg_bit();
+        let k = sample.k();
+        let upper_n = sample.upper_n();
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.set_from_dft(sample);
+        output
+    }
+
+    pub fn set_from_dft(&mut self, sample: &TrgswDft) {
+        unsafe {
+            mosfhet_sys::trgsw_from_DFT(self.ptr, sample.as_ptr() as *mut _)
+        }
+    }
+
+    pub fn from_tlwe_circuit_bootstrap(
+        input: &Tlwe,
+        key: &BootstrapKey,
+        priv_ks_key: &TrlwePrivKSKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+        l: u32,
+        bg_bit: u32,
+    ) -> Self {
+        let k = key.k();
+        let upper_n = key.upper_n();
+        let mut output = unsafe { Self::new_uninit(l, bg_bit, k, upper_n) };
+        output.set_from_tlwe_circuit_bootstrap(
+            input,
+            key,
+            priv_ks_key,
+            packing_ks_key,
+        );
+        output
+    }
+
+    pub fn set_from_tlwe_circuit_bootstrap(
+        &mut self,
+        input: &Tlwe,
+        key: &BootstrapKey,
+        priv_ks_key: &TrlwePrivKSKey,
+        packing_ks_key: &TrlwePacking1KSKey,
+    ) {
+        let k = self.k();
+        let upper_n = self.upper_n();
+        assert!(self.l() * self.bg_bit() < RawTorus::BITS);
+        assert_eq!(input.lower_n(), key.lower_n());
+        assert_eq!(k, key.k());
+        assert_eq!(upper_n, key.upper_n());
+        assert_eq!(priv_ks_key.in_lower_n(), k * upper_n);
+        assert_eq!(priv_ks_key.out_k(), k);
+        assert_eq!(priv_ks_key.out_upper_n(), upper_n);
+        assert_eq!(packing_ks_key.in_lower_n(), k * upper_n);
+        assert_eq!(packing_ks_key.out_k(), k);
+        assert_eq!(packing_ks_key.out_upper_n(), upper_n);
+        unsafe {
+            mosfhet_sys::circuit_bootstrap(
+                self.ptr,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+                priv_ks_key.as_ptr() as *mut _,
+                packing_ks_key.as_ptr() as *mut _,
+            )
+        }
+    }
+
+    fn check_params(&self, other: &Self) -> Result<()> {
+        check_eq("l", self.l(), other.l())?;
+        check_eq("bg_bit", self.bg_bit(), other.bg_bit())?;
+        check_eq("k", self.k(), other.k())?;
+        check_eq("upper_n", self.upper_n(), other.upper_n())
+    }
 }
 
-impl_load!(Trgsw => trgsw_load_new_sample(l: u32, bg_bit: u32, k: u32, lower_n: u32));
-impl_save!(Trgsw => trgsw_save_sample);
+impl_load!(Trgsw => trgsw_load_new_sample(l: u32, bg_bit: u32, k: u32, upper_n: u32));
+impl_save!(Trgsw => trgsw_save_sample(l, bg_bit, k, upper_n));
+impl_serde!(Trgsw);
 impl_drop!(Trgsw => free_trgsw);
 impl_ptrs!(Trgsw);
 
@@ -224,6 +418,15 @@ impl TrgswArray {
         Self { len, ptr }
     }
 
+    fn sample_bytes(
+        l: u32,
+        bg_bit: u32,
+        k: u32,
+        upper_n: u32,
+    ) -> Option<usize> {
+        Trgsw::sample_bytes(l, bg_bit, k, upper_n)
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
@@ -285,11 +488,7 @@ impl TrgswArray {
             .enumerate()
             .for_each(|(i, p)| {
                 let (m, e) = f(i);
-                p.set_noiseless(m);
-                if e != 0 {
-                    // TODO: support exponents
-                    unimplemented!();
-                }
+                p.set_noiseless_monomial(m, e);
             });
         output
     }
@@ -320,9 +519,10 @@ impl TrgswArray {
 }
 
 impl_load_array!(
-    TrgswArray => trgsw_load_sample(l: u32, bg_bit: u32, k: u32, lower_n: u32)
+    TrgswArray => trgsw_load_sample(l: u32, bg_bit: u32, k: u32, upper_n: u32)
 );
-impl_save_array!(TrgswArray => trgsw_save_sample);
+impl_save_array!(TrgswArray => trgsw_save_sample(l, bg_bit, k, upper_n));
+impl_serde!(TrgswArray);
 impl_drop_array!(TrgswArray => free_trgsw_array);
 impl_slice_array!(TrgswArray);
 impl_ptrs!(TrgswArray);
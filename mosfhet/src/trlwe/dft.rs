@@ -1,3 +1,4 @@
+use crate::common::format;
 use crate::common::macros::*;
 use crate::poly::DftPolynomial;
 use crate::trgsw::TrgswDft;
@@ -24,6 +25,10 @@ impl TrlweDft {
         }
     }
 
+    fn sample_bytes(k: u32, upper_n: u32) -> Option<usize> {
+        format::bytes::<f64>(&[k as usize + 1, upper_n as usize])
+    }
+
     #[cfg(feature = "rng_vaes")]
     pub fn new_compressed(m: TorusPolynomial, key: &TrlweKey) -> Self {
         Self {
@@ -159,8 +164,9 @@ impl TrlweDft {
     }
 }
 
-impl_load!(TrlweDft => trlwe_load_new_DFT_sample(k: u32, lower_n: u32));
-impl_save!(TrlweDft => trlwe_save_DFT_sample);
+impl_load!(TrlweDft => trlwe_load_new_DFT_sample(k: u32, upper_n: u32));
+impl_save!(TrlweDft => trlwe_save_DFT_sample(k, upper_n));
+impl_serde!(TrlweDft);
 impl_drop!(TrlweDft => free_trlwe);
 impl_ptrs!(TrlweDft);
 
@@ -1,5 +1,10 @@
+use crate::common::format;
+use crate::common::io::write_with;
 use crate::common::macros::*;
+use crate::common::RawTorus;
+use crate::error::{check, check_eq, check_sigma};
 use crate::tlwe::TlweKey;
+use crate::Result;
 
 #[repr(transparent)]
 pub struct TrlweKey {
@@ -8,15 +13,17 @@ pub struct TrlweKey {
 
 impl TrlweKey {
     pub fn new(upper_n: u32, k: u32, sigma: f64) -> Self {
-        Self {
-            ptr: unsafe {
-                mosfhet_sys::trlwe_new_binary_key(
-                    upper_n as i32,
-                    k as i32,
-                    sigma,
-                )
-            },
-        }
+        Self::try_new(upper_n, k, sigma).unwrap()
+    }
+
+    pub fn try_new(upper_n: u32, k: u32, sigma: f64) -> Result<Self> {
+        check(upper_n.is_power_of_two(), "upper_n", upper_n)?;
+        check(k > 0, "k", k)?;
+        check_sigma(sigma)?;
+        let ptr = unsafe {
+            mosfhet_sys::trlwe_new_binary_key(upper_n as i32, k as i32, sigma)
+        };
+        Ok(Self { ptr })
     }
 
     pub fn k(&self) -> u32 {
@@ -32,8 +39,9 @@ impl TrlweKey {
     }
 }
 
-impl_load!(TrlweKey => trlwe_load_new_key);
-impl_save!(TrlweKey => trlwe_save_key);
+impl_load!(TrlweKey => trlwe_load_new_key; upper_n, k);
+impl_save!(TrlweKey => trlwe_save_key(upper_n, k));
+impl_serde!(TrlweKey);
 impl_drop!(TrlweKey => free_trlwe_key);
 impl_ptrs!(TrlweKey);
 
@@ -52,16 +60,26 @@ impl TrlweKSKey {
         t: u32,
         base_bit: u32,
     ) -> Self {
-        Self {
-            ptr: unsafe {
-                mosfhet_sys::trlwe_new_KS_key(
-                    into_key.as_ptr() as *mut _,
-                    from_key.as_ptr() as *mut _,
-                    t as i32,
-                    base_bit as i32,
-                )
-            },
-        }
+        Self::try_new(from_key, into_key, t, base_bit).unwrap()
+    }
+
+    pub fn try_new(
+        from_key: &TrlweKey,
+        into_key: &TrlweKey,
+        t: u32,
+        base_bit: u32,
+    ) -> Result<Self> {
+        check_eq("upper_n", into_key.upper_n(), from_key.upper_n())?;
+        check_decomposition(t, base_bit)?;
+        let ptr = unsafe {
+            mosfhet_sys::trlwe_new_KS_key(
+                into_key.as_ptr() as *mut _,
+                from_key.as_ptr() as *mut _,
+                t as i32,
+                base_bit as i32,
+            )
+        };
+        Ok(Self { ptr })
     }
 
     pub fn in_k(&self) -> u32 {
@@ -75,10 +93,19 @@ impl TrlweKSKey {
     pub fn out_upper_n(&self) -> u32 {
         unsafe { (*(*(*(*(*(*self.ptr).s))).a)).N as u32 }
     }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.ptr).base_bit as u32 }
+    }
 }
 
-impl_load!(TrlweKSKey => trlwe_load_new_KS_key);
-impl_save!(TrlweKSKey => trlwe_save_KS_key);
+impl_load!(TrlweKSKey => trlwe_load_new_KS_key; in_k, out_k, out_upper_n, t, base_bit);
+impl_save!(TrlweKSKey => trlwe_save_KS_key(in_k, out_k, out_upper_n, t, base_bit));
+impl_serde!(TrlweKSKey);
 impl_drop!(TrlweKSKey => free_trlwe_ks_key);
 impl_ptrs!(TrlweKSKey);
 
@@ -97,9 +124,122 @@ impl TrlwePKSKey {
         t: u32,
         base_bit: u32,
     ) -> Self {
+        Self::try_new(from_key, into_key, t, base_bit).unwrap()
+    }
+
+    pub fn try_new(
+        from_key: &TlweKey,
+        into_key: &TrlweKey,
+        t: u32,
+        base_bit: u32,
+    ) -> Result<Self> {
+        check_decomposition(t, base_bit)?;
+        let ptr = unsafe {
+            mosfhet_sys::trlwe_new_full_packing_KS_key(
+                into_key.as_ptr() as *mut _,
+                from_key.as_ptr() as *mut _,
+                t as i32,
+                base_bit as i32,
+            )
+        };
+        Ok(Self { ptr })
+    }
+
+    pub fn in_k(&self) -> u32 {
+        unsafe { (*self.ptr).k as u32 }
+    }
+
+    pub fn out_k(&self) -> u32 {
+        unsafe { (*(*(*(*self.ptr).s))).k as u32 }
+    }
+
+    pub fn out_upper_n(&self) -> u32 {
+        unsafe { (*(*(*(*(*(*self.ptr).s))).a)).N as u32 }
+    }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.ptr).base_bit as u32 }
+    }
+}
+
+impl_load!(TrlwePKSKey => trlwe_load_new_KS_key; in_k, out_k, out_upper_n, t, base_bit);
+impl_save!(TrlwePKSKey => trlwe_save_KS_key(in_k, out_k, out_upper_n, t, base_bit));
+impl_serde!(TrlwePKSKey);
+impl_drop!(TrlwePKSKey => free_trlwe_ks_key);
+impl_ptrs!(TrlwePKSKey);
+
+unsafe impl Send for TrlwePKSKey {}
+unsafe impl Sync for TrlwePKSKey {}
+
+#[repr(transparent)]
+pub struct RelinearizationKey {
+    ptr: mosfhet_sys::TRLWE_KS_Key,
+}
+
+impl RelinearizationKey {
+    pub fn new(key: &TrlweKey, t: u32, base_bit: u32) -> Self {
+        Self::try_new(key, t, base_bit).unwrap()
+    }
+
+    pub fn try_new(key: &TrlweKey, t: u32, base_bit: u32) -> Result<Self> {
+        check_eq("k", 1, key.k())?;
+        check_decomposition(t, base_bit)?;
+        let ptr = unsafe {
+            mosfhet_sys::trlwe_new_RL_key(
+                key.as_ptr() as *mut _,
+                t as i32,
+                base_bit as i32,
+            )
+        };
+        Ok(Self { ptr })
+    }
+
+    pub fn k(&self) -> u32 {
+        unsafe { (*(*(*(*self.ptr).s))).k as u32 }
+    }
+
+    pub fn upper_n(&self) -> u32 {
+        unsafe { (*(*(*(*(*(*self.ptr).s))).a)).N as u32 }
+    }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.ptr).base_bit as u32 }
+    }
+}
+
+impl_load!(RelinearizationKey => trlwe_load_new_KS_key; k, upper_n, t, base_bit);
+impl_save!(RelinearizationKey => trlwe_save_KS_key(k, upper_n, t, base_bit));
+impl_serde!(RelinearizationKey);
+impl_drop!(RelinearizationKey => free_trlwe_ks_key);
+impl_ptrs!(RelinearizationKey);
+
+unsafe impl Send for RelinearizationKey {}
+unsafe impl Sync for RelinearizationKey {}
+
+#[repr(transparent)]
+pub struct TrlwePrivKSKey {
+    ptr: mosfhet_sys::Generic_KS_Key,
+}
+
+impl TrlwePrivKSKey {
+    pub fn new(
+        from_key: &TlweKey,
+        into_key: &TrlweKey,
+        t: u32,
+        base_bit: u32,
+    ) -> Self {
+        assert_eq!(into_key.k(), 1);
         Self {
             ptr: unsafe {
-                mosfhet_sys::trlwe_new_full_packing_KS_key(
+                mosfhet_sys::trlwe_new_priv_SK_KS_key(
                     into_key.as_ptr() as *mut _,
                     from_key.as_ptr() as *mut _,
                     t as i32,
@@ -109,30 +249,358 @@ impl TrlwePKSKey {
         }
     }
 
-    pub fn in_k(&self) -> u32 {
-        unsafe { (*self.ptr).k as u32 }
+    pub fn in_lower_n(&self) -> u32 {
+        unsafe { (*self.ptr).n as u32 }
     }
 
     pub fn out_k(&self) -> u32 {
-        unsafe { (*(*(*(*self.ptr).s))).k as u32 }
+        unsafe { (*(*(*(*(*self.ptr).s)))).k as u32 }
     }
 
     pub fn out_upper_n(&self) -> u32 {
-        unsafe { (*(*(*(*(*(*self.ptr).s))).a)).N as u32 }
+        unsafe { (*(*(*(*(*(*self.ptr).s)))).b).N as u32 }
+    }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.ptr).base_bit as u32 }
     }
 }
 
-impl_load!(TrlwePKSKey => trlwe_load_new_KS_key);
-impl_save!(TrlwePKSKey => trlwe_save_KS_key);
-impl_drop!(TrlwePKSKey => free_trlwe_ks_key);
-impl_ptrs!(TrlwePKSKey);
+impl_load!(TrlwePrivKSKey => trlwe_load_new_generic_ks_key; in_lower_n, out_k, out_upper_n, t, base_bit);
+impl_save!(TrlwePrivKSKey => trlwe_save_generic_ks_key(in_lower_n, out_k, out_upper_n, t, base_bit));
+impl_serde!(TrlwePrivKSKey);
+impl_drop!(TrlwePrivKSKey => free_trlwe_generic_ks_key);
+impl_ptrs!(TrlwePrivKSKey);
 
-unsafe impl Send for TrlwePKSKey {}
-unsafe impl Sync for TrlwePKSKey {}
+unsafe impl Send for TrlwePrivKSKey {}
+unsafe impl Sync for TrlwePrivKSKey {}
+
+#[repr(transparent)]
+pub struct TrlwePacking1KSKey {
+    ptr: mosfhet_sys::Generic_KS_Key,
+}
+
+impl TrlwePacking1KSKey {
+    pub fn new(
+        from_key: &TlweKey,
+        into_key: &TrlweKey,
+        t: u32,
+        base_bit: u32,
+    ) -> Self {
+        Self {
+            ptr: unsafe {
+                mosfhet_sys::trlwe_new_packing1_KS_key(
+                    into_key.as_ptr() as *mut _,
+                    from_key.as_ptr() as *mut _,
+                    t as i32,
+                    base_bit as i32,
+                )
+            },
+        }
+    }
+
+    pub fn in_lower_n(&self) -> u32 {
+        unsafe { (*self.ptr).n as u32 }
+    }
+
+    pub fn out_k(&self) -> u32 {
+        unsafe { (*(*(*(*(*self.ptr).s)))).k as u32 }
+    }
+
+    pub fn out_upper_n(&self) -> u32 {
+        unsafe { (*(*(*(*(*(*self.ptr).s)))).b).N as u32 }
+    }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.ptr).base_bit as u32 }
+    }
+}
+
+impl_load!(TrlwePacking1KSKey => trlwe_load_new_generic_ks_key; in_lower_n, out_k, out_upper_n, t, base_bit);
+impl_save!(TrlwePacking1KSKey => trlwe_save_generic_ks_key(in_lower_n, out_k, out_upper_n, t, base_bit));
+impl_serde!(TrlwePacking1KSKey);
+impl_drop!(TrlwePacking1KSKey => free_trlwe_generic_ks_key);
+impl_ptrs!(TrlwePacking1KSKey);
+
+unsafe impl Send for TrlwePacking1KSKey {}
+unsafe impl Sync for TrlwePacking1KSKey {}
+
+#[repr(transparent)]
+pub struct TrlweLutPackingKSKey {
+    ptr: mosfhet_sys::LUT_Packing_KS_Key,
+}
+
+impl TrlweLutPackingKSKey {
+    pub fn new(
+        from_key: &TlweKey,
+        into_key: &TrlweKey,
+        t: u32,
+        base_bit: u32,
+        torus_base: u32,
+    ) -> Self {
+        Self::try_new(from_key, into_key, t, base_bit, torus_base).unwrap()
+    }
+
+    pub fn try_new(
+        from_key: &TlweKey,
+        into_key: &TrlweKey,
+        t: u32,
+        base_bit: u32,
+        torus_base: u32,
+    ) -> Result<Self> {
+        check_eq("k", 1, into_key.k())?;
+        let valid = torus_base > 0 && torus_base <= into_key.upper_n();
+        check(valid, "torus_base", torus_base)?;
+        check_decomposition(t, base_bit)?;
+        // The key holds 2^base_bit - 1 samples per digit, counted in an int
+        check(base_bit < i32::BITS - 1, "base_bit", base_bit)?;
+        let ptr = unsafe {
+            mosfhet_sys::trlwe_new_packing_KS_key(
+                into_key.as_ptr() as *mut _,
+                from_key.as_ptr() as *mut _,
+                t as i32,
+                base_bit as i32,
+                torus_base as i32,
+            )
+        };
+        Ok(Self { ptr })
+    }
+
+    pub fn in_lower_n(&self) -> u32 {
+        unsafe { (*self.ptr).n as u32 }
+    }
+
+    pub fn out_k(&self) -> u32 {
+        unsafe { (*(*(*(*(*(*self.ptr).s))))).k as u32 }
+    }
+
+    pub fn out_upper_n(&self) -> u32 {
+        unsafe { (*(*(*(*(*(*(*self.ptr).s))))).b).N as u32 }
+    }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.ptr).base_bit as u32 }
+    }
+
+    pub fn torus_base(&self) -> u32 {
+        unsafe { (*self.ptr).torus_base as u32 }
+    }
+}
+
+impl_load!(TrlweLutPackingKSKey => trlwe_load_new_packing_KS_key; in_lower_n, out_k, out_upper_n, t, base_bit, torus_base);
+impl_save!(TrlweLutPackingKSKey => trlwe_save_packing_KS_key(in_lower_n, out_k, out_upper_n, t, base_bit, torus_base));
+impl_serde!(TrlweLutPackingKSKey);
+impl_drop!(TrlweLutPackingKSKey => free_trlwe_packing_ks_key);
+impl_ptrs!(TrlweLutPackingKSKey);
+
+unsafe impl Send for TrlweLutPackingKSKey {}
+unsafe impl Sync for TrlweLutPackingKSKey {}
+
+pub struct TrlweTracePackingKSKey {
+    keys: Vec<TrlweKSKey>,
+}
+
+impl TrlweTracePackingKSKey {
+    pub fn new(
+        from_key: &TlweKey,
+        into_key: &TrlweKey,
+        t: u32,
+        base_bit: u32,
+    ) -> Self {
+        let upper_n = into_key.upper_n();
+        assert_eq!(into_key.k(), 1);
+        assert_eq!(from_key.lower_n(), upper_n);
+        let keys = unsafe {
+            let ptr = mosfhet_sys::trlwe_new_packing1_KS_key_CDKS21(
+                into_key.as_ptr() as *mut _,
+                from_key.as_ptr() as *mut _,
+                t as i32,
+                base_bit as i32,
+            );
+            let keys = (0..upper_n.ilog2() as usize)
+                .map(|i| TrlweKSKey { ptr: *ptr.add(i) })
+                .collect();
+            libc::free(ptr as *mut _);
+            keys
+        };
+        Self { keys }
+    }
+
+    pub fn in_lower_n(&self) -> u32 {
+        self.out_upper_n()
+    }
+
+    pub fn out_k(&self) -> u32 {
+        self.keys[0].out_k()
+    }
+
+    pub fn out_upper_n(&self) -> u32 {
+        self.keys[0].out_upper_n()
+    }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.keys[0].ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.keys[0].ptr).base_bit as u32 }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const libc::c_void {
+        self.keys.as_ptr() as *const _
+    }
+
+    fn params(&self) -> [u64; 4] {
+        [self.out_upper_n(), self.out_k(), self.t(), self.base_bit()]
+            .map(u64::from)
+    }
+
+    fn read_payload(params: &[u64], payload: &mut &[u8]) -> Result<Self> {
+        let [upper_n, k, t, base_bit] = format::params(params)?;
+        let upper_n: u32 = format::param(upper_n)?;
+        check(upper_n.is_power_of_two(), "upper_n", upper_n)?;
+        check(upper_n >= 2, "upper_n", upper_n)?;
+        let params = [upper_n as u64, k, t, base_bit];
+        let keys = read_ks_keys(payload, upper_n.ilog2(), params)?;
+        Ok(Self { keys })
+    }
+
+    fn write_payload(&self, payload: &mut Vec<u8>) -> Result<()> {
+        write_ks_keys(payload, &self.keys)
+    }
+}
+
+impl_load_save!(TrlweTracePackingKSKey);
+impl_serde!(TrlweTracePackingKSKey);
+
+pub struct AutomorphismKeySet {
+    upper_n: u32,
+    keys: Vec<TrlweKSKey>,
+}
+
+impl AutomorphismKeySet {
+    pub fn new(key: &TrlweKey, t: u32, base_bit: u32) -> Self {
+        assert_eq!(key.k(), 1);
+        let upper_n = key.upper_n();
+        let keys = unsafe {
+            let ptr = mosfhet_sys::trlwe_new_automorphism_KS_keyset(
+                key.as_ptr() as *mut _,
+                true,
+                t as i32,
+                base_bit as i32,
+            );
+            let keys = (0..upper_n as usize)
+                .map(|i| TrlweKSKey { ptr: *ptr.add(i) })
+                .collect();
+            libc::free(ptr as *mut _);
+            keys
+        };
+        Self { upper_n, keys }
+    }
+
+    pub fn k(&self) -> u32 {
+        self.keys[0].out_k()
+    }
+
+    pub fn upper_n(&self) -> u32 {
+        self.upper_n
+    }
+
+    pub fn t(&self) -> u32 {
+        unsafe { (*self.keys[0].ptr).t as u32 }
+    }
+
+    pub fn base_bit(&self) -> u32 {
+        unsafe { (*self.keys[0].ptr).base_bit as u32 }
+    }
+
+    pub fn get(&self, gen: u64) -> &TrlweKSKey {
+        assert_eq!(gen & 1, 1);
+        let gen = gen % (2 * self.upper_n as u64);
+        &self.keys[(gen >> 1) as usize]
+    }
+
+    fn params(&self) -> [u64; 4] {
+        [self.upper_n(), self.k(), self.t(), self.base_bit()].map(u64::from)
+    }
+
+    fn read_payload(params: &[u64], payload: &mut &[u8]) -> Result<Self> {
+        let [upper_n, k, t, base_bit] = format::params(params)?;
+        let upper_n: u32 = format::param(upper_n)?;
+        check(upper_n.is_power_of_two(), "upper_n", upper_n)?;
+        let params = [upper_n as u64, k, t, base_bit];
+        let keys = read_ks_keys(payload, upper_n, params)?;
+        Ok(Self { upper_n, keys })
+    }
+
+    fn write_payload(&self, payload: &mut Vec<u8>) -> Result<()> {
+        write_ks_keys(payload, &self.keys)
+    }
+}
+
+impl_load_save!(AutomorphismKeySet);
+impl_serde!(AutomorphismKeySet);
+
+// MOSFHET shifts the key by 64 - (j + 1) * base_bit for each digit j < t
+fn check_decomposition(t: u32, base_bit: u32) -> Result<()> {
+    check(t > 0, "t", t)?;
+    check(base_bit > 0, "base_bit", base_bit)?;
+    let bits = t as u64 * base_bit as u64;
+    check(bits <= RawTorus::BITS as u64, "t * base_bit", bits)
+}
+
+// Reads a sequence of key switching keys between TRLWE keys sharing the
+// upper_n, k, t and base_bit given in params
+fn read_ks_keys(
+    payload: &mut &[u8],
+    count: u32,
+    params: [u64; 4],
+) -> Result<Vec<TrlweKSKey>> {
+    let keys: Vec<_> = format::parse(payload, |c_file| {
+        (0..count)
+            .map(|_| {
+                let c_file = c_file as *mut _;
+                let ptr = unsafe { mosfhet_sys::trlwe_load_new_KS_key(c_file) };
+                TrlweKSKey { ptr }
+            })
+            .collect()
+    })?;
+    let [upper_n, k, t, base_bit] = params;
+    for key in keys.iter() {
+        format::check_param("upper_n", upper_n, key.out_upper_n())?;
+        format::check_param("k", k, key.in_k())?;
+        format::check_param("k", k, key.out_k())?;
+        format::check_param("t", t, key.t())?;
+        format::check_param("base_bit", base_bit, key.base_bit())?;
+    }
+    Ok(keys)
+}
+
+fn write_ks_keys(payload: &mut Vec<u8>, keys: &[TrlweKSKey]) -> Result<()> {
+    write_with(payload, |c_file| unsafe {
+        for key in keys.iter() {
+            mosfhet_sys::trlwe_save_KS_key(c_file as *mut _, key.ptr);
+        }
+    })?;
+    Ok(())
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::format::Tag;
 
     #[test]
     fn new_load_save() {
@@ -148,4 +616,267 @@ mod tests {
         assert_eq!(key1.sigma(), key2.sigma());
         assert_eq!(key1.k(), key2.k());
     }
+
+    #[test]
+    fn relinearization_key_new_load_save() {
+        let upper_n = 256;
+        let sigma = 3.0517578125e-05;
+        let k = 1;
+        let t = 2;
+        let base_bit = 8;
+        let path = "/tmp/__trlwe_rl_key";
+        let key = TrlweKey::new(upper_n, k, sigma);
+        let rl_key1 = RelinearizationKey::new(&key, t, base_bit);
+        rl_key1.save(path).unwrap();
+        let rl_key2 = RelinearizationKey::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(rl_key1.k(), rl_key2.k());
+        assert_eq!(rl_key1.upper_n(), rl_key2.upper_n());
+        assert_eq!(rl_key1.t(), rl_key2.t());
+        assert_eq!(rl_key1.base_bit(), rl_key2.base_bit());
+    }
+
+    #[test]
+    fn priv_ks_key_new_load_save() {
+        let lower_n = 64;
+        let upper_n = 256;
+        let sigma = 3.0517578125e-05;
+        let k = 1;
+        let t = 2;
+        let base_bit = 4;
+        let path = "/tmp/__trlwe_priv_ks_key";
+        let key1 = TlweKey::new_binary(lower_n, sigma);
+        let key2 = TrlweKey::new(upper_n, k, sigma);
+        let ks_key1 = TrlwePrivKSKey::new(&key1, &key2, t, base_bit);
+        ks_key1.save(path).unwrap();
+        let ks_key2 = TrlwePrivKSKey::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(ks_key1.in_lower_n(), ks_key2.in_lower_n());
+        assert_eq!(ks_key1.out_k(), ks_key2.out_k());
+        assert_eq!(ks_key1.out_upper_n(), ks_key2.out_upper_n());
+        assert_eq!(ks_key1.t(), ks_key2.t());
+        assert_eq!(ks_key1.base_bit(), ks_key2.base_bit());
+    }
+
+    #[test]
+    fn packing1_ks_key_new_load_save() {
+        let lower_n = 64;
+        let upper_n = 256;
+        let sigma = 3.0517578125e-05;
+        let k = 1;
+        let t = 2;
+        let base_bit = 4;
+        let path = "/tmp/__trlwe_packing1_ks_key";
+        let key1 = TlweKey::new_binary(lower_n, sigma);
+        let key2 = TrlweKey::new(upper_n, k, sigma);
+        let ks_key1 = TrlwePacking1KSKey::new(&key1, &key2, t, base_bit);
+        ks_key1.save(path).unwrap();
+        let ks_key2 = TrlwePacking1KSKey::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(ks_key1.in_lower_n(), ks_key2.in_lower_n());
+        assert_eq!(ks_key1.out_k(), ks_key2.out_k());
+        assert_eq!(ks_key1.out_upper_n(), ks_key2.out_upper_n());
+        assert_eq!(ks_key1.t(), ks_key2.t());
+        assert_eq!(ks_key1.base_bit(), ks_key2.base_bit());
+    }
+
+    #[test]
+    fn automorphism_key_set_new_load_save() {
+        let upper_n = 64;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 4;
+        let base_bit = 10;
+        let path = "/tmp/__trlwe_automorphism_key_set";
+        let key = TrlweKey::new(upper_n, k, sigma);
+        let keys1 = AutomorphismKeySet::new(&key, t, base_bit);
+        keys1.save(path).unwrap();
+        let keys2 = AutomorphismKeySet::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(keys1.k(), keys2.k());
+        assert_eq!(keys1.upper_n(), keys2.upper_n());
+        assert_eq!(keys1.t(), keys2.t());
+        assert_eq!(keys1.base_bit(), keys2.base_bit());
+        for gen in (1..2 * upper_n as u64).step_by(2) {
+            assert_eq!(keys1.get(gen).out_upper_n(), upper_n);
+            assert_eq!(keys2.get(gen).out_upper_n(), upper_n);
+        }
+    }
+
+    #[test]
+    fn lut_packing_ks_key_new_load_save() {
+        let lower_n = 64;
+        let upper_n = 256;
+        let sigma = 3.0517578125e-05;
+        let k = 1;
+        let t = 2;
+        let base_bit = 4;
+        let torus_base = 4;
+        let path = "/tmp/__trlwe_lut_packing_ks_key";
+        let key1 = TlweKey::new_binary(lower_n, sigma);
+        let key2 = TrlweKey::new(upper_n, k, sigma);
+        let ks_key1 =
+            TrlweLutPackingKSKey::new(&key1, &key2, t, base_bit, torus_base);
+        ks_key1.save(path).unwrap();
+        let ks_key2 = TrlweLutPackingKSKey::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(ks_key1.in_lower_n(), ks_key2.in_lower_n());
+        assert_eq!(ks_key1.out_k(), ks_key2.out_k());
+        assert_eq!(ks_key1.out_upper_n(), ks_key2.out_upper_n());
+        assert_eq!(ks_key1.t(), ks_key2.t());
+        assert_eq!(ks_key1.base_bit(), ks_key2.base_bit());
+        assert_eq!(ks_key1.torus_base(), ks_key2.torus_base());
+    }
+
+    #[test]
+    fn trace_packing_ks_key_new_load_save() {
+        let upper_n = 256;
+        let sigma = 3.0517578125e-05;
+        let k = 1;
+        let t = 4;
+        let base_bit = 10;
+        let path = "/tmp/__trlwe_trace_packing_ks_key";
+        let key2 = TrlweKey::new(upper_n, k, sigma);
+        let key1 = TlweKey::from_trlwe_key(&key2);
+        let ks_key1 = TrlweTracePackingKSKey::new(&key1, &key2, t, base_bit);
+        ks_key1.save(path).unwrap();
+        let ks_key2 = TrlweTracePackingKSKey::load(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(ks_key1.in_lower_n(), ks_key2.in_lower_n());
+        assert_eq!(ks_key1.out_k(), ks_key2.out_k());
+        assert_eq!(ks_key1.out_upper_n(), ks_key2.out_upper_n());
+        assert_eq!(ks_key1.t(), ks_key2.t());
+        assert_eq!(ks_key1.base_bit(), ks_key2.base_bit());
+    }
+
+    #[test]
+    fn load_raw() {
+        let upper_n = 256;
+        let sigma = 3.0517578125e-05;
+        let k = 1;
+        let path = "/tmp/__trlwe_key_raw";
+        let key1 = TrlweKey::new(upper_n, k, sigma);
+        let mut bytes = Vec::new();
+        write_with(&mut bytes, |c_file| unsafe {
+            let ptr = key1.as_ptr() as *mut _;
+            mosfhet_sys::trlwe_save_key(c_file as *mut _, ptr);
+        })
+        .unwrap();
+        std::fs::write(path, &bytes).unwrap();
+        let key2 = TrlweKey::load_raw(path).unwrap();
+        assert!(TrlweKey::load(path).is_err());
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(key1.upper_n(), key2.upper_n());
+        assert_eq!(key1.k(), key2.k());
+    }
+
+    #[test]
+    fn read_param_mismatch() {
+        let key = TrlweKey::new(256, 1, 3.0517578125e-05);
+        let mut bytes = Vec::new();
+        format::write_object(&mut bytes, Tag::TrlweKey, &[256, 2], |p| {
+            write_with(p, |c_file| unsafe {
+                let ptr = key.as_ptr() as *mut _;
+                mosfhet_sys::trlwe_save_key(c_file as *mut _, ptr);
+            })?;
+            Ok(())
+        })
+        .unwrap();
+        let result = TrlweKey::read_from(&mut bytes.as_slice());
+        assert!(matches!(result, Err(crate::Error::MalformedFile(_))));
+    }
+
+    #[test]
+    fn trace_packing_ks_key_read_invalid() {
+        let mut bytes = Vec::new();
+        let params = [1, 1, 4, 10];
+        let tag = Tag::TrlweTracePackingKSKey;
+        format::write_object(&mut bytes, tag, &params, |_| Ok(())).unwrap();
+        let result = TrlweTracePackingKSKey::read_from(&mut bytes.as_slice());
+        assert!(matches!(
+            result,
+            Err(crate::Error::InvalidParameter {
+                name: "upper_n",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn automorphism_key_set_read_mismatch() {
+        let key = TrlweKey::new(64, 1, 2.2148688116005568e-16);
+        let keys = AutomorphismKeySet::new(&key, 4, 10);
+        for params in [[32, 1, 4, 10], [64, 2, 4, 10], [64, 1, 3, 10]] {
+            let mut bytes = Vec::new();
+            let tag = Tag::AutomorphismKeySet;
+            format::write_object(&mut bytes, tag, &params, |payload| {
+                write_ks_keys(payload, &keys.keys)
+            })
+            .unwrap();
+            let result = AutomorphismKeySet::read_from(&mut bytes.as_slice());
+            assert!(matches!(result, Err(crate::Error::MalformedFile(_))));
+        }
+    }
+
+    #[test]
+    fn try_new_invalid() {
+        let sigma = 3.0517578125e-05;
+        assert!(matches!(
+            TrlweKey::try_new(256, 1, 0.0),
+            Err(crate::Error::InvalidSigma { .. })
+        ));
+        assert!(matches!(
+            TrlweKey::try_new(256, 1, f64::NAN),
+            Err(crate::Error::InvalidSigma { .. })
+        ));
+        assert!(matches!(
+            TrlweKey::try_new(300, 1, sigma),
+            Err(crate::Error::InvalidParameter {
+                name: "upper_n",
+                ..
+            })
+        ));
+        assert!(matches!(
+            TrlweKey::try_new(256, 0, sigma),
+            Err(crate::Error::InvalidParameter { name: "k", .. })
+        ));
+    }
+
+    #[test]
+    fn ks_key_new_invalid() {
+        let sigma = 2.2148688116005568e-16;
+        let key1 = TrlweKey::new(256, 1, sigma);
+        let key2 = TrlweKey::new(512, 1, sigma);
+        let key3 = TrlweKey::new(256, 2, sigma);
+        let tlwe_key = TlweKey::new_binary(64, sigma);
+        assert!(matches!(
+            TrlweKSKey::try_new(&key1, &key2, 4, 10),
+            Err(crate::Error::ParameterMismatch {
+                name: "upper_n",
+                ..
+            })
+        ));
+        assert!(matches!(
+            TrlwePKSKey::try_new(&tlwe_key, &key1, 0, 10),
+            Err(crate::Error::InvalidParameter { name: "t", .. })
+        ));
+        assert!(matches!(
+            RelinearizationKey::try_new(&key3, 2, 20),
+            Err(crate::Error::ParameterMismatch { name: "k", .. })
+        ));
+        assert!(matches!(
+            RelinearizationKey::try_new(&key1, 4, 20),
+            Err(crate::Error::InvalidParameter {
+                name: "t * base_bit",
+                ..
+            })
+        ));
+        assert!(matches!(
+            TrlweLutPackingKSKey::try_new(&tlwe_key, &key1, 2, 4, 512),
+            Err(crate::Error::InvalidParameter {
+                name: "torus_base",
+                ..
+            })
+        ));
+    }
 }
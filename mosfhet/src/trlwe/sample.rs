@@ -1,11 +1,20 @@
 use std::ops::{Index, IndexMut};
 
+use rayon::prelude::*;
+
+use crate::bootstrap::{BootstrapGaKey, BootstrapKey};
 use crate::common::macros::*;
-use crate::common::Torus;
-use crate::poly::TorusPolynomial;
-use crate::tlwe::TlweArray;
+use crate::common::{format, Integer, RawTorus, Torus};
+use crate::error::{check, check_eq};
+use crate::poly::{IntPolynomial, TorusPolynomial};
+use crate::tlwe::{Tlwe, TlweArray};
 use crate::trgsw::TrgswDftArray;
-use crate::trlwe::{TrlweDft, TrlweKey, TrlwePKSKey};
+use crate::trlwe::{
+    AutomorphismKeySet, RelinearizationKey, TrlweDft, TrlweKSKey, TrlweKey,
+    TrlweLutPackingKSKey, TrlwePKSKey, TrlwePacking1KSKey,
+    TrlweTracePackingKSKey,
+};
+use crate::Result;
 
 #[repr(transparent)]
 pub struct Trlwe {
@@ -19,6 +28,10 @@ impl Trlwe {
         }
     }
 
+    fn sample_bytes(k: u32, upper_n: u32) -> Option<usize> {
+        format::bytes::<RawTorus>(&[k as usize + 1, upper_n as usize])
+    }
+
     pub fn new(m: TorusPolynomial, key: &TrlweKey) -> Self {
         Self {
             ptr: unsafe {
@@ -72,17 +85,53 @@ impl Trlwe {
         Self::new_noiseless(m, k, upper_n)
     }
 
+    pub fn from_lut_fn<F>(k: u32, upper_n: u32, torus_base: u32, f: F) -> Self
+    where
+        F: Fn(usize) -> Torus,
+    {
+        assert!(torus_base > 0);
+        assert_eq!(upper_n % torus_base, 0);
+        let slot_size = (upper_n / torus_base) as usize;
+        let poly = TorusPolynomial::from_fn(upper_n, |i| f(i / slot_size));
+        Self::new_noiseless(poly, k, upper_n)
+    }
+
+    pub fn from_full_domain_lut_fn<F>(
+        k: u32,
+        upper_n: u32,
+        precision: u32,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(usize) -> Torus,
+    {
+        assert!(precision > 1);
+        let half = 1 << (precision - 1);
+        Self::from_lut_fn(k, upper_n, 1 << precision, |i| {
+            f(i / 2 + (i & 1) * half)
+        })
+    }
+
     pub fn from_tlwe_array(
         array: &TlweArray,
         skip: usize,
         offset: usize,
         key: &TrlwePKSKey,
     ) -> Self {
+        Self::try_from_tlwe_array(array, skip, offset, key).unwrap()
+    }
+
+    pub fn try_from_tlwe_array(
+        array: &TlweArray,
+        skip: usize,
+        offset: usize,
+        key: &TrlwePKSKey,
+    ) -> Result<Self> {
         let k = key.out_k();
         let upper_n = key.out_upper_n();
         let mut output = unsafe { Self::new_uninit(k, upper_n) };
-        output.set_from_tlwe_array(array, skip, offset, key);
-        output
+        output.try_set_from_tlwe_array(array, skip, offset, key)?;
+        Ok(output)
     }
 
     pub fn set_from_tlwe_array(
@@ -92,11 +141,24 @@ impl Trlwe {
         offset: usize,
         key: &TrlwePKSKey,
     ) {
+        self.try_set_from_tlwe_array(array, skip, offset, key)
+            .unwrap()
+    }
+
+    pub fn try_set_from_tlwe_array(
+        &mut self,
+        array: &TlweArray,
+        skip: usize,
+        offset: usize,
+        key: &TrlwePKSKey,
+    ) -> Result<()> {
         let array_len = array.len();
         let required_size = skip + offset;
         let upper_n = self.upper_n() as usize;
-        assert!(array_len >= required_size);
-        assert!(offset <= upper_n);
+        check(array_len >= required_size, "skip", skip as u64)?;
+        check(offset <= upper_n, "offset", offset as u64)?;
+        check_eq("k", key.out_k(), self.k())?;
+        check_eq("upper_n", key.out_upper_n(), self.upper_n())?;
         unsafe {
             let start = array.as_ptr() as *mut mosfhet_sys::TLWE;
             mosfhet_sys::trlwe_full_packing_keyswitch(
@@ -106,6 +168,86 @@ impl Trlwe {
                 key.as_ptr() as *mut _,
             )
         }
+        Ok(())
+    }
+
+    pub fn from_tlwe_packing1(input: &Tlwe, key: &TrlwePacking1KSKey) -> Self {
+        let k = key.out_k();
+        let upper_n = key.out_upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.set_from_tlwe_packing1(input, key);
+        output
+    }
+
+    pub fn set_from_tlwe_packing1(
+        &mut self,
+        input: &Tlwe,
+        key: &TrlwePacking1KSKey,
+    ) {
+        assert_eq!(input.lower_n(), key.in_lower_n());
+        assert_eq!(self.k(), key.out_k());
+        assert_eq!(self.upper_n(), key.out_upper_n());
+        unsafe {
+            mosfhet_sys::trlwe_packing1_keyswitch(
+                self.ptr,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+            )
+        }
+    }
+
+    pub fn from_tlwe_trace_packing(
+        input: &Tlwe,
+        key: &TrlweTracePackingKSKey,
+    ) -> Self {
+        let k = key.out_k();
+        let upper_n = key.out_upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.set_from_tlwe_trace_packing(input, key);
+        output
+    }
+
+    pub fn set_from_tlwe_trace_packing(
+        &mut self,
+        input: &Tlwe,
+        key: &TrlweTracePackingKSKey,
+    ) {
+        assert_eq!(input.lower_n(), key.in_lower_n());
+        assert_eq!(self.k(), key.out_k());
+        assert_eq!(self.upper_n(), key.out_upper_n());
+        unsafe {
+            mosfhet_sys::trlwe_packing1_keyswitch_CDKS21(
+                self.ptr,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+            )
+        }
+    }
+
+    pub fn pack_lut(inputs: &TlweArray, key: &TrlweLutPackingKSKey) -> Self {
+        let k = key.out_k();
+        let upper_n = key.out_upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.pack_lut_from(inputs, key);
+        output
+    }
+
+    pub fn pack_lut_from(
+        &mut self,
+        inputs: &TlweArray,
+        key: &TrlweLutPackingKSKey,
+    ) {
+        assert_eq!(inputs.len(), key.torus_base() as usize);
+        assert_eq!(inputs.lower_n(), key.in_lower_n());
+        assert_eq!(self.k(), key.out_k());
+        assert_eq!(self.upper_n(), key.out_upper_n());
+        unsafe {
+            mosfhet_sys::trlwe_packing_keyswitch(
+                self.ptr,
+                inputs.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+            )
+        }
     }
 
     pub fn k(&self) -> u32 {
@@ -117,7 +259,14 @@ impl Trlwe {
     }
 
     pub fn add_assign(&mut self, rhs: &Self) {
+        self.try_add_assign(rhs).unwrap()
+    }
+
+    pub fn try_add_assign(&mut self, rhs: &Self) -> Result<()> {
+        check_eq("k", self.k(), rhs.k())?;
+        check_eq("upper_n", self.upper_n(), rhs.upper_n())?;
         unsafe { mosfhet_sys::trlwe_addto(self.ptr, rhs.ptr) }
+        Ok(())
     }
 
     pub fn add_from(&mut self, lhs: &Self, rhs: &Self) {
@@ -125,17 +274,28 @@ impl Trlwe {
     }
 
     pub fn add(&self, other: &Self) -> Self {
+        self.try_add(other).unwrap()
+    }
+
+    pub fn try_add(&self, other: &Self) -> Result<Self> {
         let k = self.k();
         let upper_n = self.upper_n();
-        assert_eq!(k, other.k());
-        assert_eq!(upper_n, other.upper_n());
+        check_eq("k", k, other.k())?;
+        check_eq("upper_n", upper_n, other.upper_n())?;
         let mut output = unsafe { Self::new_uninit(k, upper_n) };
         output.add_from(self, other);
-        output
+        Ok(output)
     }
 
     pub fn sub_assign(&mut self, other: &Self) {
+        self.try_sub_assign(other).unwrap()
+    }
+
+    pub fn try_sub_assign(&mut self, other: &Self) -> Result<()> {
+        check_eq("k", self.k(), other.k())?;
+        check_eq("upper_n", self.upper_n(), other.upper_n())?;
         unsafe { mosfhet_sys::trlwe_subto(self.ptr, other.ptr) }
+        Ok(())
     }
 
     pub fn sub_from(&mut self, lhs: &Self, rhs: &Self) {
@@ -143,13 +303,17 @@ impl Trlwe {
     }
 
     pub fn sub(&self, other: &Self) -> Self {
+        self.try_sub(other).unwrap()
+    }
+
+    pub fn try_sub(&self, other: &Self) -> Result<Self> {
         let k = self.k();
         let upper_n = self.upper_n();
-        assert_eq!(k, other.k());
-        assert_eq!(upper_n, other.upper_n());
+        check_eq("k", k, other.k())?;
+        check_eq("upper_n", upper_n, other.upper_n())?;
         let mut output = unsafe { Self::new_uninit(k, upper_n) };
         output.sub_from(self, other);
-        output
+        Ok(output)
     }
 
     pub fn neg_from(&mut self, input: &Self) {
@@ -164,6 +328,59 @@ impl Trlwe {
         output
     }
 
+    pub fn mul_int_from(&mut self, input: &Self, k: Integer) {
+        self.try_mul_int_from(input, k).unwrap()
+    }
+
+    pub fn try_mul_int_from(&mut self, input: &Self, k: Integer) -> Result<()> {
+        check_eq("k", self.k(), input.k())?;
+        check_eq("upper_n", self.upper_n(), input.upper_n())?;
+        unsafe { mosfhet_sys::trlwe_mul_by_int(self.ptr, input.ptr, k) }
+        Ok(())
+    }
+
+    pub fn mul_int(&self, k: Integer) -> Self {
+        let mut output = unsafe { Self::new_uninit(self.k(), self.upper_n()) };
+        output.mul_int_from(self, k);
+        output
+    }
+
+    pub fn mul_int_assign(&mut self, k: Integer) {
+        unsafe { mosfhet_sys::trlwe_mul_by_int(self.ptr, self.ptr, k) }
+    }
+
+    pub fn mul_int_poly_from(&mut self, input: &Self, poly: &IntPolynomial) {
+        self.try_mul_int_poly_from(input, poly).unwrap()
+    }
+
+    pub fn try_mul_int_poly_from(
+        &mut self,
+        input: &Self,
+        poly: &IntPolynomial,
+    ) -> Result<()> {
+        check_eq("k", self.k(), input.k())?;
+        check_eq("upper_n", self.upper_n(), input.upper_n())?;
+        check_eq("upper_n", input.upper_n(), poly.upper_n())?;
+        unsafe {
+            mosfhet_sys::trlwe_mul_by_int_polynomial(
+                self.ptr,
+                input.ptr,
+                poly.as_ptr() as *mut _,
+            )
+        }
+        Ok(())
+    }
+
+    pub fn mul_int_poly(&self, poly: &IntPolynomial) -> Self {
+        self.try_mul_int_poly(poly).unwrap()
+    }
+
+    pub fn try_mul_int_poly(&self, poly: &IntPolynomial) -> Result<Self> {
+        let mut output = unsafe { Self::new_uninit(self.k(), self.upper_n()) };
+        output.try_mul_int_poly_from(self, poly)?;
+        Ok(output)
+    }
+
     pub fn mul_by_xai_from(&mut self, source: &Self, a: u32) {
         unsafe { mosfhet_sys::trlwe_mul_by_xai(self.ptr, source.ptr, a as i32) }
     }
@@ -198,6 +415,175 @@ impl Trlwe {
         }
     }
 
+    pub fn key_switch_from(&mut self, input: &Self, key: &TrlweKSKey) {
+        self.try_key_switch_from(input, key).unwrap()
+    }
+
+    pub fn try_key_switch_from(
+        &mut self,
+        input: &Self,
+        key: &TrlweKSKey,
+    ) -> Result<()> {
+        check_eq("k", key.in_k(), input.k())?;
+        check_eq("k", key.out_k(), self.k())?;
+        check_eq("upper_n", key.out_upper_n(), input.upper_n())?;
+        check_eq("upper_n", key.out_upper_n(), self.upper_n())?;
+        unsafe {
+            mosfhet_sys::trlwe_keyswitch(
+                self.ptr,
+                input.ptr,
+                key.as_ptr() as *mut _,
+            )
+        }
+        Ok(())
+    }
+
+    pub fn key_switch(&self, key: &TrlweKSKey) -> Self {
+        self.try_key_switch(key).unwrap()
+    }
+
+    pub fn try_key_switch(&self, key: &TrlweKSKey) -> Result<Self> {
+        let k = key.out_k();
+        let upper_n = key.out_upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.try_key_switch_from(self, key)?;
+        Ok(output)
+    }
+
+    pub fn key_switch_assign(&mut self, key: &TrlweKSKey) {
+        assert_eq!(self.k(), key.in_k());
+        assert_eq!(self.k(), key.out_k());
+        assert_eq!(self.upper_n(), key.out_upper_n());
+        unsafe {
+            mosfhet_sys::trlwe_keyswitch(
+                self.ptr,
+                self.ptr,
+                key.as_ptr() as *mut _,
+            )
+        }
+    }
+
+    pub fn automorphism_from(
+        &mut self,
+        input: &Self,
+        gen: u64,
+        keys: &AutomorphismKeySet,
+    ) {
+        let upper_n = self.upper_n();
+        assert_eq!(self.k(), 1);
+        assert_eq!(input.k(), 1);
+        assert_eq!(upper_n, input.upper_n());
+        assert_eq!(upper_n, keys.upper_n());
+        unsafe {
+            mosfhet_sys::trlwe_eval_automorphism(
+                self.ptr,
+                input.ptr,
+                gen,
+                keys.get(gen).as_ptr() as *mut _,
+            )
+        }
+    }
+
+    pub fn automorphism(&self, gen: u64, keys: &AutomorphismKeySet) -> Self {
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.automorphism_from(self, gen, keys);
+        output
+    }
+
+    pub fn trace_from(&mut self, input: &Self, keys: &AutomorphismKeySet) {
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut tmp = unsafe { Self::new_uninit(k, upper_n) };
+        self.clone_from(input);
+        let mut step = upper_n as u64;
+        while step > 1 {
+            tmp.automorphism_from(self, step + 1, keys);
+            self.add_assign(&tmp);
+            step >>= 1;
+        }
+    }
+
+    pub fn trace(&self, keys: &AutomorphismKeySet) -> Self {
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.trace_from(self, keys);
+        output
+    }
+
+    pub fn expand(
+        &self,
+        count: usize,
+        keys: &AutomorphismKeySet,
+    ) -> TrlweArray {
+        let k = self.k();
+        let upper_n = self.upper_n();
+        assert!(count.is_power_of_two());
+        assert!(count <= upper_n as usize);
+        let mut output = unsafe { TrlweArray::new_uninit(count, k, upper_n) };
+        output[0].clone_from(self);
+        let mut step = 1;
+        while step < count {
+            let gen = (upper_n as usize / step) as u64 + 1;
+            let xai = 2 * upper_n - step as u32;
+            let (lhs, rhs) =
+                output.as_slice_mut()[..2 * step].split_at_mut(step);
+            lhs.par_iter_mut()
+                .zip(rhs.par_iter_mut())
+                .for_each(|(l, r)| {
+                    let mut tmp = unsafe { Self::new_uninit(k, upper_n) };
+                    tmp.automorphism_from(l, gen, keys);
+                    r.sub_from(l, &tmp);
+                    l.add_assign(&tmp);
+                    tmp.mul_by_xai_from(r, xai);
+                    r.clone_from(&tmp);
+                });
+            step <<= 1;
+        }
+        output
+    }
+
+    pub fn tensor_mul_from(
+        &mut self,
+        lhs: &Self,
+        rhs: &Self,
+        key: &RelinearizationKey,
+        precision: u32,
+    ) {
+        assert!(precision > 0 && precision < RawTorus::BITS);
+        assert_eq!(lhs.k(), 1);
+        assert_eq!(rhs.k(), 1);
+        assert_eq!(lhs.upper_n(), rhs.upper_n());
+        assert_eq!(self.k(), 1);
+        assert_eq!(self.upper_n(), lhs.upper_n());
+        assert_eq!(key.k(), 1);
+        assert_eq!(key.upper_n(), lhs.upper_n());
+        unsafe {
+            mosfhet_sys::trlwe_tensor_prod_FFT(
+                self.ptr,
+                lhs.ptr,
+                rhs.ptr,
+                precision as i32,
+                key.as_ptr() as *mut _,
+            )
+        }
+    }
+
+    pub fn tensor_mul(
+        &self,
+        other: &Self,
+        key: &RelinearizationKey,
+        precision: u32,
+    ) -> Self {
+        let k = self.k();
+        let upper_n = self.upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.tensor_mul_from(self, other, key, precision);
+        output
+    }
+
     pub fn blind_rotate(&mut self, a: &[Torus], s: &TrgswDftArray) {
         assert_eq!(a.len(), s.len());
         unsafe {
@@ -210,6 +596,94 @@ impl Trlwe {
         }
     }
 
+    pub fn functional_bootstrap_from(
+        &mut self,
+        input: &Tlwe,
+        test_vector: &Self,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) {
+        self.try_functional_bootstrap_from(input, test_vector, key, torus_base)
+            .unwrap()
+    }
+
+    pub fn try_functional_bootstrap_from(
+        &mut self,
+        input: &Tlwe,
+        test_vector: &Self,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) -> Result<()> {
+        check(torus_base > 0, "torus_base", torus_base)?;
+        check_eq("lower_n", key.lower_n(), input.lower_n())?;
+        check_eq("k", key.k(), test_vector.k())?;
+        check_eq("upper_n", key.upper_n(), test_vector.upper_n())?;
+        check_eq("k", key.k(), self.k())?;
+        check_eq("upper_n", key.upper_n(), self.upper_n())?;
+        unsafe {
+            mosfhet_sys::functional_bootstrap_wo_extract(
+                self.ptr,
+                test_vector.ptr,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+                torus_base as i32,
+            )
+        }
+        Ok(())
+    }
+
+    pub fn functional_bootstrap(
+        input: &Tlwe,
+        test_vector: &Self,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) -> Self {
+        Self::try_functional_bootstrap(input, test_vector, key, torus_base)
+            .unwrap()
+    }
+
+    pub fn try_functional_bootstrap(
+        input: &Tlwe,
+        test_vector: &Self,
+        key: &BootstrapKey,
+        torus_base: u32,
+    ) -> Result<Self> {
+        let k = test_vector.k();
+        let upper_n = test_vector.upper_n();
+        let mut output = unsafe { Self::new_uninit(k, upper_n) };
+        output.try_functional_bootstrap_from(
+            input,
+            test_vector,
+            key,
+            torus_base,
+        )?;
+        Ok(output)
+    }
+
+    pub fn functional_bootstrap_ga_from(
+        &mut self,
+        input: &Tlwe,
+        test_vector: &Self,
+        key: &BootstrapGaKey,
+        torus_base: u32,
+    ) {
+        assert!(torus_base > 0);
+        assert_eq!(input.lower_n(), key.lower_n());
+        assert_eq!(test_vector.k(), key.k());
+        assert_eq!(test_vector.upper_n(), key.upper_n());
+        assert_eq!(self.k(), key.k());
+        assert_eq!(self.upper_n(), key.upper_n());
+        unsafe {
+            mosfhet_sys::functional_bootstrap_wo_extract_ga(
+                self.ptr,
+                test_vector.ptr,
+                input.as_ptr() as *mut _,
+                key.as_ptr() as *mut _,
+                torus_base as i32,
+            )
+        }
+    }
+
     pub fn phase(&self, key: &TrlweKey) -> TorusPolynomial {
         unsafe {
             let upper_n = key.upper_n();
@@ -238,8 +712,9 @@ impl Trlwe {
     }
 }
 
-impl_load!(Trlwe => trlwe_load_new_sample(k: u32, lower_n: u32));
-impl_save!(Trlwe => trlwe_save_sample);
+impl_load!(Trlwe => trlwe_load_new_sample(k: u32, upper_n: u32));
+impl_save!(Trlwe => trlwe_save_sample(k, upper_n));
+impl_serde!(Trlwe);
 impl_drop!(Trlwe => free_trlwe);
 impl_ptrs!(Trlwe);
 
@@ -275,6 +750,10 @@ impl TrlweArray {
         Self { len, ptr }
     }
 
+    fn sample_bytes(k: u32, upper_n: u32) -> Option<usize> {
+        Trlwe::sample_bytes(k, upper_n)
+    }
+
     pub fn from_fn<F>(len: usize, key: &TrlweKey, f: F) -> Self
     where
         F: Fn(usize) -> TorusPolynomial,
@@ -302,6 +781,36 @@ impl TrlweArray {
         output
     }
 
+    pub fn from_clot21_lut_fn<F>(
+        k: u32,
+        upper_n: u32,
+        precision: u32,
+        f: F,
+    ) -> Self
+    where
+        F: Fn(usize) -> Torus,
+    {
+        Self::try_from_clot21_lut_fn(k, upper_n, precision, f).unwrap()
+    }
+
+    pub fn try_from_clot21_lut_fn<F>(
+        k: u32,
+        upper_n: u32,
+        precision: u32,
+        f: F,
+    ) -> Result<Self>
+    where
+        F: Fn(usize) -> Torus,
+    {
+        let valid = precision > 1 && precision - 1 <= upper_n.trailing_zeros();
+        check(valid, "precision", precision)?;
+        let half = 1 << (precision - 1);
+        let slot_size = upper_n as usize / half;
+        Ok(Self::from_fn_noiseless(2, k, upper_n, |j| {
+            TorusPolynomial::from_fn(upper_n, |i| f(i / slot_size + j * half))
+        }))
+    }
+
     pub fn from_elem(
         len: usize,
         key: &TrlweKey,
@@ -346,6 +855,24 @@ impl TrlweArray {
         unsafe { (*(*(*self.ptr)).b).N as u32 }
     }
 
+    pub fn key_switch_all(&self, key: &TrlweKSKey) -> Self {
+        self.try_key_switch_all(key).unwrap()
+    }
+
+    pub fn try_key_switch_all(&self, key: &TrlweKSKey) -> Result<Self> {
+        check_eq("k", key.in_k(), self.k())?;
+        check_eq("upper_n", key.out_upper_n(), self.upper_n())?;
+        let k = key.out_k();
+        let upper_n = key.out_upper_n();
+        let mut output = unsafe { Self::new_uninit(self.len, k, upper_n) };
+        output
+            .as_slice_mut()
+            .par_iter_mut()
+            .zip(self.as_slice().par_iter())
+            .for_each(|(o, i)| o.key_switch_from(i, key));
+        Ok(output)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Trlwe> {
         self.as_slice().iter()
     }
@@ -355,8 +882,9 @@ impl TrlweArray {
     }
 }
 
-impl_load_array!(TrlweArray => trlwe_load_sample(k: u32, lower_n: u32));
-impl_save_array!(TrlweArray => trlwe_save_sample);
+impl_load_array!(TrlweArray => trlwe_load_sample(k: u32, upper_n: u32));
+impl_save_array!(TrlweArray => trlwe_save_sample(k, upper_n));
+impl_serde!(TrlweArray);
 impl_drop_array!(TrlweArray => free_trlwe_array);
 impl_slice_array!(TrlweArray);
 impl_ptrs!(TrlweArray);
@@ -438,4 +966,352 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn tensor_mul() {
+        let upper_n = 1024;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 2;
+        let base_bit = 20;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let rl_key = RelinearizationKey::new(&trlwe_key, t, base_bit);
+        for precision in [3, 4] {
+            let mask = (1 << precision) - 1;
+            for x in [0, 1, 3, 7, 13] {
+                let lhs = Trlwe::new(
+                    TorusPolynomial::from_fn(upper_n, |i| {
+                        let v = if i == 0 { x & mask } else { 0 };
+                        Torus::from_unsigned(v, precision)
+                    }),
+                    &trlwe_key,
+                );
+                let rhs = Trlwe::new(
+                    TorusPolynomial::from_fn(upper_n, |i| {
+                        Torus::from_unsigned(i as RawTorus & mask, precision)
+                    }),
+                    &trlwe_key,
+                );
+                let out = lhs.tensor_mul(&rhs, &rl_key, precision as u32);
+                for (i, v) in out.phase(&trlwe_key).iter().enumerate() {
+                    assert_eq!(
+                        v.into_unsigned(precision),
+                        (x * i as RawTorus) & mask
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tensor_mul_odd_precision() {
+        let upper_n = 1024;
+        let sigma = 2.2148688116005568e-16;
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let rl_key = RelinearizationKey::new(&trlwe_key, 2, 20);
+        // An odd number of discarded bits must not double the product
+        for precision in [1, 3, 5] {
+            let one = Trlwe::new(
+                TorusPolynomial::from_fn(upper_n, |i| {
+                    Torus::from_unsigned((i == 0) as RawTorus, precision)
+                }),
+                &trlwe_key,
+            );
+            let out = one.tensor_mul(&one, &rl_key, precision as u32);
+            let phase = out.phase(&trlwe_key);
+            assert_eq!(phase[0].into_unsigned(precision), 1);
+            for v in phase.iter().skip(1) {
+                assert_eq!(v.into_unsigned(precision), 0);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn tensor_mul_output_mismatch() {
+        let upper_n = 1024;
+        let sigma = 2.2148688116005568e-16;
+        let trlwe_key = TrlweKey::new(upper_n, 1, sigma);
+        let rl_key = RelinearizationKey::new(&trlwe_key, 2, 20);
+        let input = Trlwe::zeroed(&trlwe_key);
+        let mut output = Trlwe::zeroed_noiseless(1, upper_n / 2);
+        output.tensor_mul_from(&input, &input, &rl_key, 4);
+    }
+
+    #[test]
+    fn mul_int() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let precision = 8;
+        let mask = (1 << precision) - 1;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let values = |i: usize| (i as RawTorus * 5 + 3) & mask;
+        let trlwe = Trlwe::new(
+            TorusPolynomial::from_fn(upper_n, |i| {
+                Torus::from_unsigned(values(i), precision)
+            }),
+            &trlwe_key,
+        );
+        let out = trlwe.mul_int(3);
+        for (i, v) in out.phase(&trlwe_key).iter().enumerate() {
+            assert_eq!(v.into_unsigned(precision), (values(i) * 3) & mask);
+        }
+        let mut out = trlwe.clone();
+        out.mul_int_assign(1u64.wrapping_neg());
+        for (i, v) in out.phase(&trlwe_key).iter().enumerate() {
+            assert_eq!(
+                v.into_unsigned(precision),
+                values(i).wrapping_neg() & mask
+            );
+        }
+        let poly = IntPolynomial::from_fn(upper_n, |i| match i {
+            0 => 2,
+            1 => 1u64.wrapping_neg(),
+            _ => 0,
+        });
+        let out = trlwe.mul_int_poly(&poly);
+        for (i, v) in out.phase(&trlwe_key).iter().enumerate() {
+            let prev = match i {
+                0 => values(upper_n as usize - 1).wrapping_neg(),
+                _ => values(i - 1),
+            };
+            let expected = (2 * values(i)).wrapping_sub(prev) & mask;
+            assert_eq!(v.into_unsigned(precision), expected);
+        }
+        let out = trlwe.mul_int_poly(&IntPolynomial::monomial(upper_n, 1, 5));
+        let expected = trlwe.mul_by_xai(5).phase(&trlwe_key);
+        for (v, x) in out.phase(&trlwe_key).iter().zip(expected.iter()) {
+            assert_eq!(v.into_unsigned(precision), x.into_unsigned(precision));
+        }
+    }
+
+    #[test]
+    fn automorphism() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 4;
+        let base_bit = 10;
+        let precision = 8;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let keys = AutomorphismKeySet::new(&trlwe_key, t, base_bit);
+        let m = TorusPolynomial::from_fn(upper_n, |i| {
+            Torus::from_unsigned((i as RawTorus * 7 + 3) & 255, precision)
+        });
+        let trlwe = Trlwe::new(m.clone(), &trlwe_key);
+        for gen in [1, 3, 5, 257, 511] {
+            let out = trlwe.automorphism(gen, &keys);
+            let expected = m.permute(gen);
+            for (v, e) in out.phase(&trlwe_key).iter().zip(expected.iter()) {
+                assert_eq!(
+                    v.into_unsigned(precision),
+                    e.into_unsigned(precision)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn trace() {
+        let upper_n = 256;
+        let log_n = 8;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 4;
+        let base_bit = 10;
+        let precision = 4;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let keys = AutomorphismKeySet::new(&trlwe_key, t, base_bit);
+        let m = TorusPolynomial::from_fn(upper_n, |i| {
+            let v = if i == 0 { 5 } else { i as RawTorus & 15 };
+            Torus::from_unsigned(v, precision + log_n)
+        });
+        let trlwe = Trlwe::new(m, &trlwe_key);
+        let out = trlwe.trace(&keys);
+        for (i, v) in out.phase(&trlwe_key).iter().enumerate() {
+            let expected = if i == 0 { 5 } else { 0 };
+            assert_eq!(v.into_unsigned(precision), expected);
+        }
+    }
+
+    #[test]
+    fn expand() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 4;
+        let base_bit = 10;
+        let precision = 4;
+        let mask = (1 << precision) - 1;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let keys = AutomorphismKeySet::new(&trlwe_key, t, base_bit);
+        for log_count in [4, 8] {
+            let m = TorusPolynomial::from_fn(upper_n, |i| {
+                let v = (i as RawTorus * 3) & mask;
+                Torus::from_unsigned(v, precision + log_count)
+            });
+            let trlwe = Trlwe::new(m, &trlwe_key);
+            let out = trlwe.expand(1 << log_count, &keys);
+            assert_eq!(out.len(), 1 << log_count);
+            for (i, sample) in out.iter().enumerate() {
+                let v = sample.phase(&trlwe_key)[0];
+                assert_eq!(
+                    v.into_unsigned(precision),
+                    (i as RawTorus * 3) & mask
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn packing1_key_switch() {
+        let lower_n = 128;
+        let sigma_1 = 9.5367431640625e-07;
+        let upper_n = 256;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 6;
+        let base_bit = 4;
+        let precision = 6;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let ks_key =
+            TrlwePacking1KSKey::new(&tlwe_key, &trlwe_key, t, base_bit);
+        for x in [0, 1, 17, 63] {
+            let m = Torus::from_unsigned(x, precision);
+            let tlwe = Tlwe::new(m, &tlwe_key);
+            let trlwe = Trlwe::from_tlwe_packing1(&tlwe, &ks_key);
+            for (i, v) in trlwe.phase(&trlwe_key).iter().enumerate() {
+                let expected = if i == 0 { x } else { 0 };
+                assert_eq!(v.into_unsigned(precision), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn trace_packing_key_switch() {
+        let upper_n = 256;
+        let log_n = 8;
+        let sigma = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 4;
+        let base_bit = 10;
+        let precision = 6;
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma);
+        let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
+        let ks_key =
+            TrlweTracePackingKSKey::new(&tlwe_key, &trlwe_key, t, base_bit);
+        for x in [0, 1, 17, 63] {
+            let m = Torus::from_unsigned(x, precision + log_n);
+            let tlwe = Tlwe::new(m, &tlwe_key);
+            let trlwe = Trlwe::from_tlwe_trace_packing(&tlwe, &ks_key);
+            for (i, v) in trlwe.phase(&trlwe_key).iter().enumerate() {
+                let expected = if i == 0 { x } else { 0 };
+                assert_eq!(v.into_unsigned(precision), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn lut_packing_key_switch() {
+        let lower_n = 128;
+        let sigma_1 = 9.5367431640625e-07;
+        let upper_n = 256;
+        let sigma_2 = 2.2148688116005568e-16;
+        let k = 1;
+        let t = 6;
+        let base_bit = 4;
+        let torus_base = 8;
+        let precision = 6;
+        let tlwe_key = TlweKey::new_binary(lower_n, sigma_1);
+        let trlwe_key = TrlweKey::new(upper_n, k, sigma_2);
+        let ks_key = TrlweLutPackingKSKey::new(
+            &tlwe_key, &trlwe_key, t, base_bit, torus_base,
+        );
+        let values = |i: usize| (i as RawTorus * 11 + 5) & 63;
+        let tlwes = TlweArray::from_fn(torus_base as usize, &tlwe_key, |i| {
+            Torus::from_unsigned(values(i), precision)
+        });
+        let trlwe = Trlwe::pack_lut(&tlwes, &ks_key);
+        let slot = (upper_n / torus_base) as usize;
+        for (i, v) in trlwe.phase(&trlwe_key).iter().enumerate() {
+            assert_eq!(v.into_unsigned(precision), values(i / slot));
+        }
+    }
+
+    #[test]
+    fn key_switch() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let t = 4;
+        let base_bit = 10;
+        let precision = 8;
+        let mask = (1 << precision) - 1;
+        let key1 = TrlweKey::new(upper_n, 1, sigma);
+        let key2 = TrlweKey::new(upper_n, 1, sigma);
+        let key3 = TrlweKey::new(upper_n, 2, sigma);
+        let ks_key12 = TrlweKSKey::new(&key1, &key2, t, base_bit);
+        let ks_key21 = TrlweKSKey::new(&key2, &key1, t, base_bit);
+        let ks_key31 = TrlweKSKey::new(&key3, &key1, t, base_bit);
+        let values = |i: usize| (i as RawTorus * 5 + 1) & mask;
+        let m = TorusPolynomial::from_fn(upper_n, |i| {
+            Torus::from_unsigned(values(i), precision)
+        });
+        let check = |c: &Trlwe, key: &TrlweKey| {
+            for (i, v) in c.phase(key).iter().enumerate() {
+                assert_eq!(v.into_unsigned(precision), values(i));
+            }
+        };
+
+        let c1 = Trlwe::new(m.clone(), &key1);
+        let mut c2 = c1.key_switch(&ks_key12);
+        check(&c2, &key2);
+        c2.key_switch_assign(&ks_key21);
+        check(&c2, &key1);
+
+        let c3 = Trlwe::new(m.clone(), &key3);
+        let c1 = c3.key_switch(&ks_key31);
+        check(&c1, &key1);
+
+        let array = TrlweArray::from_fn(8, &key1, |_| m.clone());
+        let output = array.key_switch_all(&ks_key12);
+        assert_eq!(output.len(), 8);
+        assert_eq!(output.k(), 1);
+        output.iter().for_each(|c| check(c, &key2));
+    }
+
+    #[test]
+    fn write_read() {
+        let upper_n = 256;
+        let sigma = 2.2148688116005568e-16;
+        let key = TrlweKey::new(upper_n, 2, sigma);
+        let poly = TorusPolynomial::from_fn(upper_n, |i| {
+            Torus::from_unsigned(i as RawTorus, 8)
+        });
+        let trlwe = Trlwe::new(poly.clone(), &key);
+        let dft_poly = DftPolynomial::from_torus(&poly);
+        let trlwe_dft = TrlweDft::new_noiseless(dft_poly, 2, upper_n);
+        let trlwes = TrlweArray::from_elem(3, &key, poly);
+        let mut bytes = Vec::new();
+        trlwe.write_to(&mut bytes).unwrap();
+        trlwe_dft.write_to(&mut bytes).unwrap();
+        trlwes.write_to(&mut bytes).unwrap();
+        let mut reader = bytes.as_slice();
+        let mut again = Vec::new();
+        Trlwe::read_from(&mut reader)
+            .unwrap()
+            .write_to(&mut again)
+            .unwrap();
+        TrlweDft::read_from(&mut reader)
+            .unwrap()
+            .write_to(&mut again)
+            .unwrap();
+        TrlweArray::read_from(&mut reader)
+            .unwrap()
+            .write_to(&mut again)
+            .unwrap();
+        assert!(reader.is_empty());
+        assert_eq!(bytes, again);
+    }
 }
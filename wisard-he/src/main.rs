@@ -18,6 +18,7 @@ use atomic_float::AtomicF64;
 
 use indicatif::ParallelProgressIterator;
 use indicatif::{ProgressBar, ProgressFinish, ProgressStyle};
+use mosfhet::params::KeySet;
 use mosfhet::prelude::*;
 use rayon::prelude::*;
 use thread_local::ThreadLocal;
@@ -155,42 +156,27 @@ fn main() {
         eprintln!("Arguments: {opts:#?}");
     }
 
-    if opts.sigma <= 0.0 || opts.sigma >= 1.0 {
-        panic!("The sigma parameter must be between 0.0 and 1.0.");
-    }
-
-    if !opts.upper_n.is_power_of_two() {
-        panic!("The upper_n parameter must be a power of two.");
-    }
+    let param = |name: &str, value: u64| {
+        u32::try_from(value).unwrap_or_else(|error| {
+            panic!("Invalid {name} parameter {value}: {error}.")
+        })
+    };
 
-    if opts.upper_n.get() < 512 || opts.upper_n.get() > 4096 {
+    let params = ParamSet::builder()
+        .upper_n(param("upper_n", opts.upper_n.get().into()))
+        .k(1)
+        .sigma(opts.sigma)
+        .l(param("l", opts.l.get().into()))
+        .bg_bit(param("bg_bit", opts.bg_bit.get().into()))
+        .t(param("t", opts.t.get().into()))
+        .base_bit(param("base_bit", opts.base_bit.get().into()))
+        .build()
+        .unwrap_or_else(|error| panic!("Invalid TFHE parameters: {error}."));
+
+    if params.upper_n() < 512 || params.upper_n() > 4096 {
         panic!("The upper_n parameter must be between 512 and 4096.");
     }
 
-    if opts.l.get() > 54 {
-        panic!("The l parameter must be between 1 and 54.");
-    }
-
-    if opts.bg_bit.get() > 54 {
-        panic!("The Bg_bit parameter must be between 1 and 54.");
-    }
-
-    if opts.l.get() * opts.bg_bit.get() > 54 {
-        panic!("The product between l and Bg_bit must be between 1 and 54.");
-    }
-
-    if opts.t.get() > 54 {
-        panic!("The t parameter must be between 1 and 54.");
-    }
-
-    if opts.base_bit.get() > 54 {
-        panic!("The base_bit parameter must be between 1 and 54.");
-    }
-
-    if opts.t.get() * opts.base_bit.get() > 54 {
-        panic!("The product between t and base_bit must be between 1 and 54.");
-    }
-
     // Set the thread pool size
     rayon::ThreadPoolBuilder::new()
         .num_threads(opts.threads.get())
@@ -298,26 +284,27 @@ fn main() {
     }
 
     // TFHE parameters
-    let sigma = opts.sigma;
-    let base_bit = opts.base_bit.get() as u32;
-    let upper_n = opts.upper_n.get() as u32;
-    let bg_bit = opts.bg_bit.get() as u32;
-    let l = opts.l.get() as u32;
-    let t = opts.t.get() as u32;
-    let k = 1;
+    let upper_n = params.upper_n();
+    let k = params.k();
+
+    if opts.verbose {
+        let security = params.security().bits();
+        eprintln!("Sec. level: {security:.1} bits");
+    }
 
     // Generate new keys
-    let trlwe_key = TrlweKey::new(upper_n, k, sigma);
-    let trgsw_key = TrgswKey::new(&trlwe_key, l, bg_bit);
-    let tlwe_key = TlweKey::from_trlwe_key(&trlwe_key);
-    let pks_key = TrlwePKSKey::new(&tlwe_key, &trlwe_key, t, base_bit);
+    let KeySet {
+        trlwe_key,
+        trgsw_key,
+        tlwe_key,
+        pks_key,
+    } = params.keys();
 
     // Calculate useful information
     let log_upper_n = upper_n.ilog2() as usize;
     let lut_vp_depth = addr_label_size.saturating_sub(log_upper_n);
     let lut_vp_count = 1usize << lut_vp_depth;
     let trn_low_size = addr_label_size.min(log_upper_n);
-    let inf_low_size = addr_size.min(log_upper_n);
     let lower_label_bits = label_size.saturating_sub(lut_vp_depth);
     let upper_label_bits = label_size - lower_label_bits;
     let upper_lut_size = 1 << (lut_vp_depth - upper_label_bits);
@@ -340,6 +327,12 @@ fn main() {
     // Instantiate thread local storage
     let tls = ThreadLocal::new();
 
+    // Define a zeroed LUT over all address-label pairs and its increment
+    let lut_size = 1 << addr_label_size;
+    let zlut =
+        EncryptedLut::from_fn_noiseless(lut_size, k, upper_n, |_| Torus::MIN);
+    let one = Torus::from_unsigned(1, count_bits);
+
     // Get the total number of training addresses
     let train_addr_count = train_addresses.len();
 
@@ -382,14 +375,6 @@ fn main() {
         // Convert index to usize
         let index = index as usize;
 
-        // Generate and encrypt a zeroed LUT containing one on first index
-        let mut enc_mask = TrlweArray::from_fn(lut_vp_count, &trlwe_key, |i| {
-            TorusPolynomial::from_fn(upper_n, |j| {
-                let value = i == 0 && j == 0;
-                Torus::from_unsigned(value as RawTorus, count_bits)
-            })
-        });
-
         // Concatenate and encrypt address and label
         let addr_label = ((label as usize) << addr_size) | (addr as usize);
         let enc_addr_label =
@@ -405,41 +390,15 @@ fn main() {
         }
 
         // Instantiate thread-local LUTs
-        let enc_luts = tls.get_or(|| {
-            let zlut = TrlweArray::zeroed_noiseless(lut_vp_count, k, upper_n);
-            RefCell::new(vec![zlut; num_luts_disc])
-        });
-
-        // Split the bits for blind-rotation and cdemux-tree
-        let (lower, upper) = enc_addr_label.as_slice().split_at(trn_low_size);
+        let enc_luts =
+            tls.get_or(|| RefCell::new(vec![zlut.clone(); num_luts_disc]));
 
         // Measure server iteration time
         #[cfg(feature = "time-tracking")]
         let begin = Instant::now();
 
-        // Apply a left-handed blind rotation
-        for (i, bit) in lower.iter().enumerate().rev() {
-            // Compute the rotation of the polynomial by 2^i
-            let rot = enc_mask[0].mul_by_xai_pred(1 << i);
-
-            // Conditionally apply the rotation based on the selector bit
-            let rot = Trlwe::from_dft(&TrlweDft::mul_trlwe_dft(&rot, bit));
-
-            // Apply the rotation over the original LUT
-            enc_mask[0].add_assign(&rot);
-        }
-
-        // Apply the CDEMUX tree
-        for (i, bit) in upper.iter().enumerate().rev() {
-            // Apply CDEMUX between an strided vector of elements
-            bit.cdemux_vectored(enc_mask.as_slice_mut(), 1 << i);
-        }
-
-        // Train the LUTs using the mask
-        let mut enc_luts = enc_luts.borrow_mut();
-        for (lut, mask) in enc_luts[index].iter_mut().zip(enc_mask.iter()) {
-            lut.add_assign(mask);
-        }
+        // Train the LUT by incrementing the encrypted address
+        enc_luts.borrow_mut()[index].increment(&enc_addr_label, one);
 
         // Store elapsed server time
         #[cfg(feature = "time-tracking")]
@@ -472,15 +431,12 @@ fn main() {
     }
 
     // Combine thread local results
-    let zlut = TrlweArray::zeroed_noiseless(lut_vp_count, k, upper_n);
     let mut enc_luts =
         tls.into_iter()
             .fold(vec![zlut; num_luts_disc], |mut accs, luts| {
                 let iter = accs.iter_mut().zip(luts.into_inner());
-                for (accs, luts) in iter {
-                    for (acc, lut) in accs.iter_mut().zip(luts.iter()) {
-                        acc.add_assign(lut);
-                    }
+                for (acc, lut) in iter {
+                    acc.add_assign(&lut);
                 }
                 accs
             });
@@ -491,7 +447,8 @@ fn main() {
         enc_luts = enc_luts
             .into_iter()
             .map(|luts| {
-                TrlweArray::from_fn(lut_vp_count, &trlwe_key, |i| {
+                let luts = luts.as_trlwe_array();
+                let luts = TrlweArray::from_fn(lut_vp_count, &trlwe_key, |i| {
                     let poly = luts[i].phase(&trlwe_key);
                     TorusPolynomial::from_fn(upper_n, |i| {
                         Torus::from_unsigned(
@@ -499,7 +456,8 @@ fn main() {
                             count_bits,
                         )
                     })
-                })
+                });
+                EncryptedLut::new(luts, lut_size)
             })
             .collect();
 
@@ -605,9 +563,6 @@ fn main() {
             // Convert index to usize
             let index = index as usize;
 
-            // Split the selectors for blind-rotation and cmux-tree
-            let (lower, upper) = enc_addr.as_slice().split_at(inf_low_size);
-
             #[cfg(feature = "noise-tracking")]
             let sample_iter = sample_results
                 .0
@@ -624,42 +579,15 @@ fn main() {
                 #[cfg(feature = "time-tracking")]
                 let begin = Instant::now();
 
-                // Separate upper and lower label bits
-                let upper_label = label >> lower_label_bits;
-                let lower_label = label & ((1 << lower_label_bits) - 1);
-
-                // Calculate upper and lower LUT offsets
-                let upper_offset = upper_label * upper_lut_size;
-                let lower_offset = lower_label * lower_lut_size;
-
-                // Clone the original LUT for in-place vertical packing
-                let mut lut = enc_luts[index].as_slice()
-                    [upper_offset..upper_offset + upper_lut_size]
-                    .to_vec();
-
-                // Apply the CMUX tree
-                for (i, bit) in upper.iter().enumerate() {
-                    // Apply CMUX between an strided vector of elements
-                    bit.cmux_vectored(lut.as_mut_slice(), 1 << i);
-                }
-
-                // Apply a right-handed blind rotation
-                for (i, bit) in lower.iter().enumerate() {
-                    // Compute the rotation of the polynomial by 2N - 2^i
-                    let after = lut[0].mul_by_xai_pred(upper_n * 2 - (1 << i));
-
-                    // Apply the rotation based on the selector bit
-                    let trlwe_dft = TrlweDft::mul_trlwe_dft(&after, bit);
-                    let after = Trlwe::from_dft(&trlwe_dft);
-
-                    lut[0].add_assign(&after);
-                }
+                // The label selects the table slice holding its addresses
+                let offset = label << addr_size;
 
+                // Read the slice at the encrypted address
                 #[cfg(feature = "noise-tracking")]
-                result.0.set_from_trlwe(&lut[0], lower_offset);
+                enc_luts[index].read_offset_into(result.0, &enc_addr, offset);
 
                 #[cfg(not(feature = "noise-tracking"))]
-                result.set_from_trlwe(&lut[0], lower_offset);
+                enc_luts[index].read_offset_into(result, &enc_addr, offset);
 
                 #[cfg(feature = "time-tracking")]
                 {
@@ -676,7 +604,8 @@ fn main() {
                     let lo_addr = addr_label & (upper_n as usize - 1);
 
                     // Obtain the original LUT
-                    let original_lut = &enc_luts[index][up_addr];
+                    let original_lut =
+                        &enc_luts[index].as_trlwe_array()[up_addr];
 
                     // Extract the value before processing
                     let bef = &Tlwe::from_trlwe(original_lut, lo_addr);